@@ -3,20 +3,22 @@ use vg::*;
 main!(my_game);
 
 async fn my_game() {
-    println!("Hello world");
+    log::info!("Hello world");
 
-    let mut time = 0.0f32;
+    set_resolution(V(320, 180), ScaleMode::Integer, BLACK);
 
     loop {
+        let angle = time() * 6.0;
         let color = V(0.0, 0.5, 0.2, 1.0);
 
         let start = V(20.0, 20.0);
-        let mid = V(30.0 + time.sin() * 15.0, 30.0 + time.cos() * 15.0);
+        let mid = V(30.0 + angle.sin() * 15.0, 30.0 + angle.cos() * 15.0);
         let end = V(40.0, 60.0);
 
-        line(color, [start, mid, end]);
-
-        time += 0.1;
+        line(Stroke::new(color).width(3.0).join(Join::Round), [start, mid, end]);
+        circle(mid, 4.0, Style::fill(WHITE).with_stroke(color));
+        let style = TextStyle::new(WHITE).size(24.0).align(Align::Center);
+        text(screen_size() / 2.0, "Hello world", style);
 
         present().await;
     }
@@ -0,0 +1,83 @@
+use std::{io::Cursor, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+
+use crate::{Asset, AssetKind, Assets, BinAsset};
+
+/// Sound decoded into interleaved samples in -1 to 1 range
+pub struct AudioAsset {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Arc<Vec<f32>>,
+}
+
+impl AudioAsset {
+    /// Number of samples per channel
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+}
+
+impl AssetKind for AudioAsset {
+    /// Encoded WAV or Ogg Vorbis file
+    type Data = Asset<BinAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let bin = data.get()?;
+
+        let decoded = match bin.bytes.get(..4) {
+            Some(b"RIFF") => decode_wav(&bin.bytes),
+            Some(b"OggS") => decode_ogg(&bin.bytes),
+            _ => Err(anyhow!("Unknown audio format")),
+        };
+
+        match decoded {
+            Ok(audio) => Some(audio),
+            Err(error) => {
+                tracing::debug!(%error, "Failed to decode audio");
+                None
+            }
+        }
+    }
+}
+
+fn decode_wav(bytes: &[u8]) -> Result<AudioAsset> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1u32 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 * scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    Ok(AudioAsset {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        samples: Arc::new(samples),
+    })
+}
+
+fn decode_ogg(bytes: &[u8]) -> Result<AudioAsset> {
+    let mut reader = lewton::inside_ogg::OggStreamReader::new(Cursor::new(bytes))?;
+
+    let mut samples = vec![];
+    while let Some(packet) = reader.read_dec_packet_itl()? {
+        samples.extend(packet.into_iter().map(|sample| sample as f32 / 32768.0));
+    }
+
+    Ok(AudioAsset {
+        sample_rate: reader.ident_hdr.audio_sample_rate,
+        channels: reader.ident_hdr.audio_channels as u16,
+        samples: Arc::new(samples),
+    })
+}
@@ -0,0 +1,38 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{Asset, AssetKind, Assets, BinAsset};
+
+/// TrueType or OpenType font file, possibly a collection
+pub struct FontAsset {
+    pub bytes: Arc<Vec<u8>>,
+}
+
+impl FontAsset {
+    /// Check the file starts with a known font signature
+    fn is_font(bytes: &[u8]) -> bool {
+        const SIGNATURES: [&[u8]; 4] = [b"\x00\x01\x00\x00", b"OTTO", b"true", b"ttcf"];
+        SIGNATURES.iter().any(|sig| bytes.starts_with(sig))
+    }
+}
+
+impl AssetKind for FontAsset {
+    /// Font file contents
+    type Data = Asset<BinAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let bin = data.get()?;
+
+        if !FontAsset::is_font(&bin.bytes) {
+            tracing::debug!("Not a font file");
+            return None;
+        }
+
+        Some(FontAsset {
+            bytes: Arc::new(bin.bytes.clone()),
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{Asset, AssetKind, Assets, BinAsset};
+
+/// Image decoded into 8-bit RGBA pixels, row by row
+pub struct ImageAsset {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Arc<Vec<u8>>,
+}
+
+impl AssetKind for ImageAsset {
+    /// Encoded image file
+    type Data = Asset<BinAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let bin = data.get()?;
+
+        let image = match image::load_from_memory(&bin.bytes) {
+            Ok(image) => image.into_rgba8(),
+            Err(error) => {
+                tracing::debug!(%error, "Failed to decode image");
+                return None;
+            }
+        };
+
+        Some(ImageAsset {
+            width: image.width(),
+            height: image.height(),
+            rgba: Arc::new(image.into_raw()),
+        })
+    }
+}
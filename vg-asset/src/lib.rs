@@ -2,8 +2,14 @@
 
 mod asset;
 mod assets;
+mod audio;
 mod file;
+mod font;
+mod image;
 
 pub use asset::{Asset, AssetKind, BinAsset};
 pub use assets::Assets;
+pub use audio::AudioAsset;
 pub use file::FileSource;
+pub use font::FontAsset;
+pub use image::ImageAsset;
@@ -16,6 +16,8 @@ pub struct Live {
     history: Vec<SaveState>,
     max_reached: RuntimeInstant,
     scale: f32,
+    /// Latest crash of the game and when it happened
+    crash: Option<(RuntimeInstant, GuestError)>,
 }
 
 impl Live {
@@ -31,6 +33,7 @@ impl Live {
             history: vec![],
             max_reached: RuntimeInstant::EPOCH,
             scale: 8.0,
+            crash: None,
         }
     }
 
@@ -47,6 +50,13 @@ impl Live {
         });
 
         self.history_ui(ui);
+        self.crash_ui(ui);
+
+        // Debug draws are dropped in release builds, like the F12 toggle
+        let mut debug_overlay = self.engine.debug_overlay();
+        if cfg!(debug_assertions) && ui.checkbox(&mut debug_overlay, "Debug overlay").changed() {
+            self.engine.set_debug_overlay(debug_overlay);
+        }
 
         if ui.button("End").clicked() || !self.engine.alive() {
             return Some(self.engine.config_mut().clone());
@@ -69,7 +79,16 @@ impl Live {
 
         // Potentially run a single step forward
         match self.engine.poll() {
-            PollResult::None => (),
+            PollResult::None => {
+                // The game crashed, go back to where the user is looking
+                if let Some(error) = self.engine.guest_error() {
+                    self.crash = Some((self.engine.runtime_instant(), error.clone()));
+
+                    if let Some(goto) = self.goto {
+                        self.rewind(goto);
+                    }
+                }
+            }
             // State has advanced
             PollResult::Tick => {
                 if self.engine.runtime_instant().frames_since(
@@ -84,14 +103,23 @@ impl Live {
 
                 // Roll back to previous state
                 if let Some(goto) = self.goto {
-                    self.goto_save(goto).unwrap();
+                    self.rewind(goto);
                 }
             }
+            // Engine is no longer alive, which ends the session
+            PollResult::Exited { code } => tracing::info!("Game exited with code {code}"),
         }
 
         self.max_reached = self.max_reached.max(self.engine.runtime_instant());
     }
 
+    /// Go to an instant, leaving the engine where it is on failure
+    fn rewind(&mut self, instant: RuntimeInstant) {
+        if let Err(e) = self.goto_save(instant) {
+            tracing::warn!("Failed to go to {instant}: {e}");
+        }
+    }
+
     /// Inserts the current save state into it's chronological position
     #[profiling::function]
     fn push_save(&mut self) {
@@ -135,6 +163,16 @@ impl Live {
         // Run until instant is found
         while self.engine.runtime_instant() < instant {
             self.engine.poll();
+
+            if self.engine.guest_error().is_some() {
+                return Err(anyhow!(
+                    "Game crashed at {}",
+                    self.engine.runtime_instant()
+                ));
+            }
+            if let Some(code) = self.engine.exit_code() {
+                return Err(anyhow!("Game exited with code {code}"));
+            }
         }
 
         tracing::trace!(
@@ -174,7 +212,10 @@ impl Live {
             {
                 match goto {
                     None => *goto = Some(now),
-                    Some(_) => *goto = None,
+                    Some(_) => {
+                        *goto = None;
+                        self.crash = None;
+                    }
                 }
             }
 
@@ -283,6 +324,28 @@ impl Live {
             });
     }
 
+    /// Show the latest crash, with a way back to before it
+    fn crash_ui(&mut self, ui: &mut Ui) {
+        let Some((instant, error)) = &self.crash else {
+            return;
+        };
+        let instant = *instant;
+
+        ui.colored_label(Color32::LIGHT_RED, format!("Game crashed at {instant}"));
+        ScrollArea::vertical()
+            .id_source("crash")
+            .max_height(200.0)
+            .show(ui, |ui| ui.monospace(error.to_string()));
+
+        // The tick at the crash instant is the one that failed
+        if ui.button("Rewind to last good frame").clicked() {
+            self.goto = match instant.frames_since(RuntimeInstant::EPOCH) {
+                0 => Some(instant),
+                _ => Some(instant.prev_frame()),
+            };
+        }
+    }
+
     /// Time range we have of rollback saves
     fn saved_range(&self) -> Range<RuntimeInstant> {
         let first = self.history.first().map(SaveState::instant);
@@ -1,4 +1,4 @@
-use egui::{TextEdit, Ui};
+use egui::{DragValue, TextEdit, Ui};
 use egui_winit::winit::{event::Event, event_loop::EventLoopWindowTarget};
 use vg_engine::EngineConfig;
 
@@ -18,7 +18,11 @@ enum Lifecycle {
 impl Controller {
     pub fn new() -> Controller {
         Controller {
-            lifecycle: Lifecycle::Dead(EngineConfig::new()),
+            // The timeline can go back to any tick of the session
+            lifecycle: Lifecycle::Dead(EngineConfig {
+                keep_input_log: true,
+                ..EngineConfig::new()
+            }),
         }
     }
 
@@ -43,6 +47,15 @@ impl Controller {
                 // Runtime execution
                 ui.label("Game entrypoint");
                 ui.add(TextEdit::singleline(&mut config.path).code_editor());
+                // Ticks run as fast as the engine is polled, this only sets the
+                // time step games see
+                ui.label("Simulated tick rate");
+                ui.add(
+                    DragValue::new(&mut config.tick_rate)
+                        .clamp_range(1..=1000)
+                        .suffix(" Hz"),
+                )
+                .on_hover_text("Time step reported to the game. Doesn't change playback speed");
 
                 // Presentation
                 ui.checkbox(&mut config.headless, "Run in headless mode");
@@ -0,0 +1,280 @@
+//! Sound mixing that follows rollback
+//!
+//! Every sound is identified by the tick that started it and its order within
+//! that tick. Rewinding doesn't stop sounds started after the restored
+//! instant, it only makes them unconfirmed. Simulating their tick again either
+//! confirms them, so nothing is started twice, or fades them out
+
+use std::{collections::HashMap, sync::Mutex};
+
+use vg_asset::{Asset, Assets, AudioAsset};
+
+use crate::{prelude::*, RuntimeInstant};
+
+/// Sample rate used when there is no audio device to match
+pub const DEFAULT_SAMPLE_RATE: u32 = 48000;
+/// Length of the fade applied to cancelled sounds, in seconds
+const FADE_OUT: f32 = 0.05;
+/// How many ticks finished sounds are remembered for. Rewinding further than
+/// this may replay them
+const HISTORY_TICKS: isize = 600;
+/// How far the audio device may lag behind the simulation, in seconds. Sounds
+/// end this long after their clip does even when nothing mixes them
+const LATENCY: f32 = 0.5;
+
+/// Sound started by the game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    pub path: String,
+    pub volume: f32,
+    pub pan: f32,
+    pub looping: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VoiceState {
+    Playing,
+    /// Started after the instant rewound to, waiting for its tick to run again
+    Unconfirmed,
+    /// Not started again by its tick, with the remaining gain
+    FadingOut(f32),
+    /// Reached its end, remembered so it isn't played again
+    Finished,
+}
+
+struct Voice {
+    instant: RuntimeInstant,
+    /// Order among the sounds started by the same tick
+    index: usize,
+    sound: Sound,
+    /// Position in the source, in frames
+    position: f64,
+    state: VoiceState,
+}
+
+pub struct Mixer {
+    assets: Arc<Assets>,
+    sample_rate: u32,
+    /// Simulated ticks per second
+    tick_rate: u32,
+    /// Sounds requested by the game, by asset path
+    clips: HashMap<String, Asset<AudioAsset>>,
+    voices: Vec<Voice>,
+}
+
+impl Mixer {
+    pub fn new(assets: Arc<Assets>, tick_rate: u32) -> Mixer {
+        Mixer {
+            assets,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            tick_rate,
+            clips: HashMap::new(),
+            voices: vec![],
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Record the sounds started by a simulated tick
+    pub fn tick(&mut self, instant: RuntimeInstant, sounds: Vec<Sound>) {
+        for (index, sound) in sounds.into_iter().enumerate() {
+            let existing = self.voices.iter_mut().find(|voice| {
+                voice.instant == instant
+                    && voice.index == index
+                    && voice.sound == sound
+                    && !matches!(voice.state, VoiceState::FadingOut(_))
+            });
+
+            match existing {
+                // Already playing or played, since the first time this tick ran
+                Some(voice) => {
+                    if voice.state == VoiceState::Unconfirmed {
+                        voice.state = VoiceState::Playing;
+                    }
+                }
+                None => {
+                    let assets = &self.assets;
+                    self.clips
+                        .entry(sound.path.clone())
+                        .or_insert_with(|| assets.get(&sound.path));
+                    self.voices.push(Voice {
+                        instant,
+                        index,
+                        sound,
+                        position: 0.0,
+                        state: VoiceState::Playing,
+                    });
+                }
+            }
+        }
+
+        // The tick no longer starts these
+        for voice in &mut self.voices {
+            if voice.instant == instant && voice.state == VoiceState::Unconfirmed {
+                voice.state = VoiceState::FadingOut(1.0);
+            }
+        }
+
+        // Sounds also end in simulated time, as headless runs may never mix
+        let tick_rate = self.tick_rate as f32;
+        let seconds = |voice: &Voice| instant.frames_since(voice.instant) as f32 / tick_rate;
+        for voice in &mut self.voices {
+            if voice.state != VoiceState::Playing || voice.sound.looping {
+                continue;
+            }
+            let Some(clip) = self.clips.get_mut(&voice.sound.path).and_then(Asset::get) else {
+                continue;
+            };
+            let length = clip.frames() as f32 / clip.sample_rate as f32;
+            if seconds(voice) >= length + LATENCY {
+                voice.state = VoiceState::Finished;
+            }
+        }
+
+        self.voices.retain(|voice| match voice.state {
+            VoiceState::Finished => instant.frames_since(voice.instant) < HISTORY_TICKS,
+            // Fades start on the tick of the sound
+            VoiceState::FadingOut(_) => seconds(voice) < FADE_OUT + LATENCY,
+            VoiceState::Playing | VoiceState::Unconfirmed => true,
+        });
+    }
+
+    /// The engine went back to `instant`, and will simulate the following
+    /// ticks again
+    pub fn rewind(&mut self, instant: RuntimeInstant) {
+        for voice in &mut self.voices {
+            if voice.instant >= instant && voice.state == VoiceState::Playing {
+                voice.state = VoiceState::Unconfirmed;
+            }
+        }
+    }
+
+    /// Mix all playing sounds into interleaved stereo samples
+    pub fn mix(&mut self, out: &mut [f32]) {
+        out.fill(0.0);
+
+        let fade_step = 1.0 / (FADE_OUT * self.sample_rate as f32);
+        let Mixer {
+            clips,
+            voices,
+            sample_rate,
+            ..
+        } = self;
+
+        for voice in voices.iter_mut() {
+            let mut gain = match voice.state {
+                VoiceState::Playing | VoiceState::Unconfirmed => 1.0,
+                VoiceState::FadingOut(gain) => gain,
+                VoiceState::Finished => continue,
+            };
+
+            // Sounds start once they have loaded
+            let Some(clip) = clips.get_mut(&voice.sound.path).and_then(Asset::get) else {
+                if let VoiceState::FadingOut(_) = voice.state {
+                    voice.state = VoiceState::FadingOut(0.0);
+                }
+                continue;
+            };
+            let frames = clip.frames();
+            let channels = clip.channels as usize;
+            if frames == 0 {
+                voice.state = VoiceState::Finished;
+                continue;
+            }
+
+            let step = clip.sample_rate as f64 / *sample_rate as f64;
+            let pan = voice.sound.pan.clamp(-1.0, 1.0);
+            let left_gain = voice.sound.volume * (1.0 - pan).min(1.0);
+            let right_gain = voice.sound.volume * (1.0 + pan).min(1.0);
+
+            for frame in out.chunks_exact_mut(2) {
+                if voice.position >= frames as f64 {
+                    if !voice.sound.looping {
+                        voice.state = VoiceState::Finished;
+                        break;
+                    }
+                    voice.position %= frames as f64;
+                }
+
+                // Mono sources play on both sides
+                let i = voice.position as usize * channels;
+                let left = clip.samples[i];
+                let right = clip.samples[i + (channels > 1) as usize];
+                frame[0] += left * left_gain * gain;
+                frame[1] += right * right_gain * gain;
+                voice.position += step;
+
+                if let VoiceState::FadingOut(_) = voice.state {
+                    gain -= fade_step;
+                    if gain <= 0.0 {
+                        break;
+                    }
+                }
+            }
+
+            if let VoiceState::FadingOut(_) = voice.state {
+                voice.state = VoiceState::FadingOut(gain);
+            }
+        }
+
+        // Faded out sounds were never started as far as the game knows
+        voices.retain(|voice| !matches!(voice.state, VoiceState::FadingOut(gain) if gain <= 0.0));
+    }
+}
+
+/// Plays a mixer through the default audio device
+pub struct AudioOutput {
+    #[cfg(not(target_os = "android"))]
+    _stream: cpal::Stream,
+}
+
+impl AudioOutput {
+    #[cfg(not(target_os = "android"))]
+    pub fn new(mixer: Arc<Mutex<Mixer>>) -> Result<AudioOutput> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(anyhow!("No audio output device"))?;
+        let config: cpal::StreamConfig = device.default_output_config()?.into();
+        let channels = config.channels as usize;
+        mixer.lock().unwrap().set_sample_rate(config.sample_rate.0);
+
+        let mut stereo = vec![];
+        let stream = device.build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                stereo.resize(data.len() / channels * 2, 0.0);
+                mixer.lock().unwrap().mix(&mut stereo);
+
+                for (out, frame) in data.chunks_exact_mut(channels).zip(stereo.chunks_exact(2)) {
+                    match out {
+                        [mono] => *mono = (frame[0] + frame[1]) / 2.0,
+                        [left, right, rest @ ..] => {
+                            *left = frame[0];
+                            *right = frame[1];
+                            rest.fill(0.0);
+                        }
+                        [] => (),
+                    }
+                }
+            },
+            |e| error!("Audio output error: {e}"),
+            None,
+        )?;
+        stream.play()?;
+
+        Ok(AudioOutput { _stream: stream })
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn new(_mixer: Arc<Mutex<Mixer>>) -> Result<AudioOutput> {
+        Err(anyhow!("Audio output is not supported on Android yet"))
+    }
+}
@@ -0,0 +1,118 @@
+//! Gamepad polling. Not available on Android yet, where pads are never seen
+
+#[cfg(not(target_os = "android"))]
+use vg_interface::{PadAxis, PadButton};
+
+use crate::{
+    input::{InputState, PadEvent},
+    prelude::*,
+};
+
+pub struct Gamepads {
+    #[cfg(not(target_os = "android"))]
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl Gamepads {
+    pub fn new() -> Gamepads {
+        #[cfg(not(target_os = "android"))]
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                error!("Gamepad initialization error: {e}");
+                None
+            }
+        };
+
+        Gamepads {
+            #[cfg(not(target_os = "android"))]
+            gilrs,
+        }
+    }
+
+    /// Forward gamepad events received since the last poll
+    #[cfg(not(target_os = "android"))]
+    pub fn poll(&mut self, input: &mut InputState) {
+        use gilrs::{Event, EventType};
+
+        let Some(gilrs) = &mut self.gilrs else { return };
+
+        while let Some(Event { id, event, .. }) = gilrs.next_event() {
+            let event = match event {
+                EventType::Connected => PadEvent::Connected,
+                EventType::Disconnected => PadEvent::Disconnected,
+                EventType::ButtonPressed(button, _) => match map_button(button) {
+                    Some(button) => PadEvent::Pressed(button),
+                    None => continue,
+                },
+                EventType::ButtonReleased(button, _) => match map_button(button) {
+                    Some(button) => PadEvent::Released(button),
+                    None => continue,
+                },
+                // Analog triggers are reported as buttons
+                EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => {
+                    PadEvent::Axis(PadAxis::LeftTrigger, value)
+                }
+                EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => {
+                    PadEvent::Axis(PadAxis::RightTrigger, value)
+                }
+                EventType::AxisChanged(axis, value, _) => match map_axis(axis) {
+                    Some(axis) => PadEvent::Axis(axis, value),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            input.pad_event(id.into(), event);
+        }
+
+        // Pads that were plugged in before we started don't announce themselves
+        for (id, _) in gilrs.gamepads() {
+            input.pad_event(id.into(), PadEvent::Connected);
+        }
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn poll(&mut self, _input: &mut InputState) {}
+}
+
+#[cfg(not(target_os = "android"))]
+fn map_button(button: gilrs::Button) -> Option<PadButton> {
+    use gilrs::Button as B;
+
+    Some(match button {
+        B::South => PadButton::South,
+        B::East => PadButton::East,
+        B::North => PadButton::North,
+        B::West => PadButton::West,
+        B::LeftTrigger => PadButton::LeftBumper,
+        B::RightTrigger => PadButton::RightBumper,
+        B::LeftTrigger2 => PadButton::LeftTrigger,
+        B::RightTrigger2 => PadButton::RightTrigger,
+        B::Select => PadButton::Select,
+        B::Start => PadButton::Start,
+        B::Mode => PadButton::Mode,
+        B::LeftThumb => PadButton::LeftStick,
+        B::RightThumb => PadButton::RightStick,
+        B::DPadUp => PadButton::DPadUp,
+        B::DPadDown => PadButton::DPadDown,
+        B::DPadLeft => PadButton::DPadLeft,
+        B::DPadRight => PadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+#[cfg(not(target_os = "android"))]
+fn map_axis(axis: gilrs::Axis) -> Option<PadAxis> {
+    use gilrs::Axis as A;
+
+    Some(match axis {
+        A::LeftStickX => PadAxis::LeftStickX,
+        A::LeftStickY => PadAxis::LeftStickY,
+        A::RightStickX => PadAxis::RightStickX,
+        A::RightStickY => PadAxis::RightStickY,
+        A::LeftZ => PadAxis::LeftTrigger,
+        A::RightZ => PadAxis::RightTrigger,
+        _ => return None,
+    })
+}
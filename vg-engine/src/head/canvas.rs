@@ -1,21 +1,45 @@
-use std::num::NonZeroUsize;
+use std::{collections::HashMap, num::NonZeroUsize};
 
-use vg_interface::Draw;
+use vg_asset::{Asset, Assets};
+use vg_interface::{self as vg, Align, Draw, Paint, PathSegment, Space, Style, Transform};
 use wgpu::*;
 
-use crate::{prelude::*, runtime::WorldState};
-use vello::{kurbo::Stroke, AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
+use super::{
+    image::{draw_image, CanvasImage},
+    text::{draw_text, CanvasFont},
+};
+use crate::{prelude::*, runtime::WorldState, GuestError};
+use vg_scene::Object;
+use vello::{
+    kurbo::{self, Affine, BezPath, Cap, Ellipse, Join, PathEl, Point, Rect, Shape, Stroke},
+    peniko::{Brush, Color, ColorStop, Extend, Fill, Font, Gradient, Image, Mix},
+    AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene,
+};
+
+/// Maximum error when flattening shapes into paths
+const SHAPE_TOLERANCE: f64 = 0.1;
+/// Longest miter between the pieces of a perimeter outline, relative to its
+/// width, before the corner is beveled
+const PERIMETER_MITER_LIMIT: f64 = 4.0;
+/// Text size of the crash report, in pixels
+const ERROR_TEXT_SIZE: f32 = 14.0;
 
 pub struct Canvas {
     device: Arc<Device>,
     queue: Arc<Queue>,
     renderer: Renderer,
     format: TextureFormat,
+    assets: Arc<Assets>,
+    /// Fonts requested by the game, by asset path
+    fonts: HashMap<String, Asset<CanvasFont>>,
+    default_font: CanvasFont,
+    /// Images requested by the game, by asset path
+    images: HashMap<String, Asset<CanvasImage>>,
 }
 
 #[profile_all]
 impl Canvas {
-    pub fn new(device: Arc<Device>, queue: Arc<Queue>) -> Result<Canvas> {
+    pub fn new(device: Arc<Device>, queue: Arc<Queue>, assets: Arc<Assets>) -> Result<Canvas> {
         // Default
         let format = TextureFormat::Rgba8Unorm;
 
@@ -35,6 +59,10 @@ impl Canvas {
             queue,
             renderer,
             format,
+            assets,
+            fonts: HashMap::new(),
+            default_font: CanvasFont::default_font(),
+            images: HashMap::new(),
         })
     }
 
@@ -58,38 +86,78 @@ impl Canvas {
         .unwrap();
     }
 
-    pub fn render(&mut self, surface: &SurfaceTexture, world: &WorldState) {
+    pub fn render(
+        &mut self,
+        surface: &SurfaceTexture,
+        world: &WorldState,
+        error: Option<&GuestError>,
+        debug: bool,
+    ) {
         let mut scene = Scene::new();
 
-        for draw in &world.draws {
-            match draw {
-                Draw::Line {
-                    color: (r, g, b, a),
-                    points,
-                } => {
-                    for [x, y] in points.array_windows() {
-                        scene.stroke(
-                            &Stroke::new(1.0),
-                            Default::default(),
-                            vello::peniko::Color::rgba(*r as _, *g as _, *b as _, *a as _),
-                            None,
-                            &vello::kurbo::Line::new(
-                                (x.0 as f64, x.1 as f64),
-                                (y.0 as f64, y.1 as f64),
-                            ),
-                        );
-                    }
-                }
+        // Map the virtual resolution onto the surface
+        let (width, height) = (surface.texture.width(), surface.texture.height());
+        let placement = world.placement(Vec2::new(width as f32, height as f32));
+        let view = Affine::translate((placement.offset.x as f64, placement.offset.y as f64))
+            * Affine::scale_non_uniform(placement.scale.x as f64, placement.scale.y as f64);
+
+        if let Some(resolution) = &world.resolution {
+            let surface_rect = Rect::new(0.0, 0.0, width as f64, height as f64);
+            let clear = color(resolution.clear);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, clear, None, &surface_rect);
+        }
+        if placement.clip {
+            let visible = Rect::new(0.0, 0.0, placement.visible.x as _, placement.visible.y as _);
+            scene.push_layer(Mix::Clip, 1.0, view, &visible);
+        }
+
+        // World space is drawn once per camera, each clipped to its viewport
+        let canvas_size = (placement.visible.x, placement.visible.y);
+        let cameras = match world.cameras.as_slice() {
+            [] => vec![(Affine::IDENTITY, None)],
+            cameras => cameras
+                .iter()
+                .map(|camera| {
+                    let (min, max) = camera.viewport_rect(canvas_size);
+                    let rect = Rect::from_points(point(min), point(max));
+                    (affine(&camera.transform(canvas_size)), Some(rect))
+                })
+                .collect(),
+        };
+        for (camera, rect) in cameras {
+            if let Some(rect) = rect {
+                scene.push_layer(Mix::Clip, 1.0, view, &rect);
+            }
+            self.draw_space(&mut scene, view * camera, world, Space::World, debug);
+            if rect.is_some() {
+                scene.pop_layer();
             }
         }
 
-        scene.fill(
-            vello::peniko::Fill::NonZero,
-            vello::kurbo::Affine::IDENTITY,
-            vello::peniko::Color::rgb8(242, 140, 168),
-            None,
-            &vello::kurbo::Circle::new((420.0, 200.0), 120.0),
-        );
+        // View space stays fixed on the canvas, over the world
+        self.draw_space(&mut scene, view, world, Space::View, debug);
+
+        if placement.clip {
+            scene.pop_layer();
+        }
+
+        // Report a crash over the last frame, in surface pixels
+        if let Some(error) = error {
+            let surface_rect = Rect::new(0.0, 0.0, width as f64, height as f64);
+            let shade = Color::rgba(0.0, 0.0, 0.0, 0.8);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, shade, None, &surface_rect);
+
+            let text = format!("The game has crashed\n\n{error}");
+            draw_text(
+                &mut scene,
+                Affine::translate((16.0, 16.0)),
+                &self.default_font.0,
+                &text,
+                ERROR_TEXT_SIZE,
+                &Brush::Solid(Color::rgb(1.0, 0.4, 0.4)),
+                Align::Left,
+            );
+        }
 
         self.renderer
             .render_to_surface(
@@ -99,11 +167,620 @@ impl Canvas {
                 surface,
                 &RenderParams {
                     base_color: vello::peniko::Color::TRANSPARENT,
-                    width: surface.texture.width(),
-                    height: surface.texture.height(),
+                    width,
+                    height,
                     antialiasing_method: AaConfig::Area,
                 },
             )
             .unwrap();
     }
 }
+
+impl Canvas {
+    /// Draw everything in one space. Retained objects go under the draws of
+    /// the latest tick, with gizmos on top if enabled
+    fn draw_space(
+        &mut self,
+        scene: &mut Scene,
+        base: Affine,
+        world: &WorldState,
+        space: Space,
+        debug: bool,
+    ) {
+        for object in world.scene.objects() {
+            match (object.transform, space) {
+                (vg_scene::Space::World(matrix), Space::World)
+                | (vg_scene::Space::View(matrix), Space::View) => {
+                    self.draw_object(scene, base * mat_affine(matrix), object);
+                }
+                _ => (),
+            }
+        }
+
+        let gizmos = world.debug_draws.iter().filter(|_| debug);
+        let calls = world.draws.iter().chain(gizmos);
+        for call in calls.filter(|call| call.space == space) {
+            self.draw(scene, base * affine(&call.transform), &call.draw);
+        }
+    }
+
+    fn draw(&mut self, scene: &mut Scene, transform: Affine, draw: &Draw) {
+        match draw {
+            Draw::Line { stroke, points } => {
+                self.stroke_shape(scene, transform, &polyline(points), stroke);
+            }
+            Draw::Rect { min, max, style } => {
+                let rect = Rect::from_points(point(*min), point(*max));
+                self.draw_shape(scene, transform, &rect, style);
+            }
+            Draw::Ellipse {
+                center,
+                radii,
+                style,
+            } => {
+                let ellipse = Ellipse::new(point(*center), point(*radii).to_vec2(), 0.0);
+                self.draw_shape(scene, transform, &ellipse, style);
+            }
+            Draw::Polygon { points, style } => {
+                let mut path = polyline(points);
+                path.close_path();
+                self.draw_shape(scene, transform, &path, style);
+            }
+            Draw::Path { segments, style } => {
+                self.draw_shape(scene, transform, &bez_path(segments), style);
+            }
+            Draw::Text {
+                text,
+                font,
+                size,
+                paint,
+                position,
+                align,
+            } => {
+                // Skip the text until its font and paint have loaded
+                let Some((brush, _)) = self.brush(paint) else {
+                    return;
+                };
+                let Some(font) = self.font(font.as_deref()) else {
+                    return;
+                };
+                let transform = transform * Affine::translate(point(*position).to_vec2());
+                draw_text(scene, transform, font, text, *size, &brush, *align);
+            }
+            Draw::Image {
+                path,
+                src_rect,
+                dst_transform,
+                tint,
+            } => {
+                // Skip the image until it has loaded
+                let Some(image) = self.image(path) else {
+                    return;
+                };
+                let src = match src_rect {
+                    Some((min, max)) => Rect::from_points(point(*min), point(*max)),
+                    None => Rect::new(0.0, 0.0, image.width as f64, image.height as f64),
+                };
+                let transform = transform * affine(dst_transform);
+                draw_image(scene, transform, image, src, color(*tint));
+            }
+        }
+    }
+
+    /// Get a loaded font by path, or the default font
+    fn font(&mut self, path: Option<&str>) -> Option<&Font> {
+        let Some(path) = path else {
+            return Some(&self.default_font.0);
+        };
+
+        let assets = &self.assets;
+        let font = self
+            .fonts
+            .entry(path.to_string())
+            .or_insert_with(|| assets.get(path));
+        font.get().map(|font| &font.0)
+    }
+
+    /// Get a loaded image by path
+    fn image(&mut self, path: &str) -> Option<&Image> {
+        let assets = &self.assets;
+        let image = self
+            .images
+            .entry(path.to_string())
+            .or_insert_with(|| assets.get(path));
+        image.get().map(|image| &image.0)
+    }
+
+    /// Brush of a paint, with its transform relative to the shape. None
+    /// while an image is loading
+    fn brush(&mut self, paint: &Paint) -> Option<(Brush, Option<Affine>)> {
+        let stops = |stops: &[vg::ColorStop]| {
+            stops
+                .iter()
+                .map(|stop| ColorStop {
+                    offset: stop.offset,
+                    color: color(stop.color),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let brush = match paint {
+            Paint::Solid(c) => Brush::Solid(color(*c)),
+            Paint::Linear {
+                start,
+                end,
+                stops: s,
+                extend: e,
+            } => {
+                let gradient = Gradient::new_linear(point(*start), point(*end));
+                gradient_brush(gradient, &stops(s), extend(*e))
+            }
+            Paint::Radial {
+                center,
+                radius,
+                stops: s,
+                extend: e,
+            } => {
+                let gradient = Gradient::new_radial(point(*center), *radius);
+                gradient_brush(gradient, &stops(s), extend(*e))
+            }
+            Paint::Sweep {
+                center,
+                start_angle,
+                end_angle,
+                stops: s,
+                extend: e,
+            } => {
+                let gradient = Gradient::new_sweep(point(*center), *start_angle, *end_angle);
+                gradient_brush(gradient, &stops(s), extend(*e))
+            }
+            Paint::Image {
+                path,
+                transform,
+                extend: e,
+            } => {
+                let image = self.image(path)?.clone().with_extend(extend(*e));
+                return Some((Brush::Image(image), Some(affine(transform))));
+            }
+        };
+
+        Some((brush, None))
+    }
+
+    /// Brush of a scene paint, like `brush`
+    fn scene_brush(&mut self, paint: &vg_scene::Paint) -> Option<(Brush, Option<Affine>)> {
+        let stops = |stops: &[(f32, Vec4)]| {
+            stops
+                .iter()
+                .map(|&(offset, color)| ColorStop {
+                    offset,
+                    color: vec_color(color),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let brush = match paint {
+            vg_scene::Paint::Solid(color) => Brush::Solid(vec_color(*color)),
+            vg_scene::Paint::Linear {
+                start,
+                end,
+                stops: s,
+                extend,
+            } => {
+                let gradient = Gradient::new_linear(vec_point(*start), vec_point(*end));
+                gradient_brush(gradient, &stops(s), scene_extend(*extend))
+            }
+            vg_scene::Paint::Radial {
+                center,
+                radius,
+                stops: s,
+                extend,
+            } => {
+                let gradient = Gradient::new_radial(vec_point(*center), *radius);
+                gradient_brush(gradient, &stops(s), scene_extend(*extend))
+            }
+            vg_scene::Paint::Sweep {
+                center,
+                start_angle,
+                end_angle,
+                stops: s,
+                extend,
+            } => {
+                let gradient = Gradient::new_sweep(vec_point(*center), *start_angle, *end_angle);
+                gradient_brush(gradient, &stops(s), scene_extend(*extend))
+            }
+            vg_scene::Paint::Image {
+                path,
+                transform,
+                extend,
+            } => {
+                let image = self.image(path)?.clone().with_extend(scene_extend(*extend));
+                let transform = Affine::new(transform.to_cols_array().map(|c| c as f64));
+                return Some((Brush::Image(image), Some(transform)));
+            }
+        };
+
+        Some((brush, None))
+    }
+
+    /// Fill and then outline a shape
+    fn draw_shape(
+        &mut self,
+        scene: &mut Scene,
+        transform: Affine,
+        shape: &impl Shape,
+        style: &Style,
+    ) {
+        let fill = style.fill.as_ref().and_then(|fill| self.brush(fill));
+        if let Some((brush, brush_transform)) = fill {
+            scene.fill(Fill::NonZero, transform, &brush, brush_transform, shape);
+        }
+
+        if let Some(stroke) = &style.stroke {
+            self.stroke_shape(scene, transform, shape, stroke);
+        }
+    }
+
+    fn stroke_shape(
+        &mut self,
+        scene: &mut Scene,
+        transform: Affine,
+        shape: &impl Shape,
+        stroke: &vg::Stroke,
+    ) {
+        let Some((brush, brush_transform)) = self.brush(&stroke.paint) else {
+            return;
+        };
+
+        let style = Stroke::new(stroke.width as f64)
+            .with_caps(match stroke.cap {
+                vg::Cap::Butt => Cap::Butt,
+                vg::Cap::Round => Cap::Round,
+                vg::Cap::Square => Cap::Square,
+            })
+            .with_join(match stroke.join {
+                vg::Join::Bevel => Join::Bevel,
+                vg::Join::Miter => Join::Miter,
+                vg::Join::Round => Join::Round,
+            })
+            .with_miter_limit(stroke.miter_limit as f64)
+            .with_dashes(
+                stroke.dash_offset as f64,
+                stroke.dashes.iter().map(|&d| d as f64),
+            );
+
+        match stroke.space {
+            Space::World => scene.stroke(&style, transform, &brush, brush_transform, shape),
+            // Transform the outline ourselves so the width stays in pixels. The
+            // paint still moves with the shape
+            Space::View => {
+                let mut path = shape.to_path(SHAPE_TOLERANCE);
+                path.apply_affine(transform);
+                let brush_transform = transform * brush_transform.unwrap_or(Affine::IDENTITY);
+                scene.stroke(
+                    &style,
+                    Affine::IDENTITY,
+                    &brush,
+                    Some(brush_transform),
+                    &path,
+                );
+            }
+        }
+    }
+
+    /// Fill and then outline a retained object
+    fn draw_object(&mut self, scene: &mut Scene, transform: Affine, object: &Object) {
+        let points = |points: &[Vec2]| points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        let path = match &object.shape {
+            vg_scene::Shape::Rect { min, max } => {
+                Rect::from_points(vec_point(*min), vec_point(*max)).to_path(SHAPE_TOLERANCE)
+            }
+            vg_scene::Shape::Ellipse { center, radii } => {
+                Ellipse::new(vec_point(*center), vec_point(*radii).to_vec2(), 0.0)
+                    .to_path(SHAPE_TOLERANCE)
+            }
+            vg_scene::Shape::Polygon(vertices) => {
+                let mut path = polyline(&points(vertices));
+                path.close_path();
+                path
+            }
+            vg_scene::Shape::Line(vertices) => polyline(&points(vertices)),
+        };
+        let closed = !matches!(object.shape, vg_scene::Shape::Line(_));
+
+        let fill = object.fill.as_ref().filter(|_| closed);
+        let fill = fill.and_then(|fill| self.scene_brush(&fill.paint));
+        if let Some((brush, brush_transform)) = fill {
+            scene.fill(Fill::NonZero, transform, &brush, brush_transform, &path);
+        }
+
+        let Some(stroke) = &object.stroke else {
+            return;
+        };
+        let (width, path, transform, shape_transform) = match stroke.thickness {
+            vg_scene::Space::World(width) => (width, path, transform, Affine::IDENTITY),
+            // Transform the outline ourselves so the width stays in pixels
+            vg_scene::Space::View(width) => (width, transform * path, Affine::IDENTITY, transform),
+        };
+
+        match &stroke.paint {
+            vg_scene::StrokePaint::Paint(paint) => {
+                let Some((brush, brush_transform)) = self.scene_brush(paint) else {
+                    return;
+                };
+                // The paint still moves with the shape
+                let brush_transform = shape_transform * brush_transform.unwrap_or(Affine::IDENTITY);
+                let style = Stroke::new(width as f64);
+                scene.stroke(&style, transform, &brush, Some(brush_transform), &path);
+            }
+            vg_scene::StrokePaint::Perimeter(colors) => {
+                stroke_perimeter(scene, transform, &path, width as f64, colors, closed);
+            }
+        }
+    }
+}
+
+/// Outline a path with colors blended evenly around its perimeter. Every
+/// straight piece gets its own gradient, sharing mitered corners with the
+/// pieces next to it so they don't overlap
+fn stroke_perimeter(
+    scene: &mut Scene,
+    transform: Affine,
+    path: &BezPath,
+    width: f64,
+    colors: &[Vec4],
+    closed: bool,
+) {
+    if colors.is_empty() {
+        return;
+    }
+
+    // Straight pieces of every subpath, along with whether it loops back
+    let mut lines: Vec<(Vec<Point>, bool)> = vec![];
+    path.flatten(SHAPE_TOLERANCE, |element| match element {
+        PathEl::MoveTo(p) => lines.push((vec![p], false)),
+        PathEl::LineTo(p) => {
+            let Some((points, _)) = lines.last_mut() else {
+                return;
+            };
+            if points
+                .last()
+                .is_some_and(|last| last.distance(p) > f64::EPSILON)
+            {
+                points.push(p);
+            }
+        }
+        PathEl::ClosePath => {
+            let Some((points, looped)) = lines.last_mut() else {
+                return;
+            };
+            if points.len() > 1 && points[0].distance(points[points.len() - 1]) <= f64::EPSILON {
+                points.pop();
+            }
+            *looped = true;
+        }
+        PathEl::QuadTo(..) | PathEl::CurveTo(..) => unreachable!("Flattened paths are lines"),
+    });
+
+    let total = lines
+        .iter()
+        .flat_map(|(points, looped)| pieces(points, *looped))
+        .map(|(a, b)| a.distance(b))
+        .sum::<f64>();
+    if total <= f64::EPSILON {
+        return;
+    }
+
+    let half = width / 2.0;
+    let mut along = 0.0;
+    for (points, looped) in &lines {
+        let looped = *looped && points.len() > 2;
+        let normals: Vec<_> = pieces(points, looped)
+            .map(|(a, b)| {
+                let direction = (b - a).normalize();
+                kurbo::Vec2::new(-direction.y, direction.x)
+            })
+            .collect();
+        let count = normals.len();
+
+        // Offset to the left edge at a point of the line. None where the
+        // pieces meet too sharply for a miter, so each ends square instead
+        let corner = |i: usize| {
+            let (before, after) = match looped {
+                false if i == 0 => return Some(normals[0] * half),
+                false if i == count => return Some(normals[count - 1] * half),
+                _ => (normals[(i + count - 1) % count], normals[i % count]),
+            };
+            let miter = (before + after).normalize();
+            let cos = miter.dot(after);
+            (cos > 1.0 / PERIMETER_MITER_LIMIT).then(|| miter * (half / cos))
+        };
+
+        for (i, (start, end)) in pieces(points, looped).enumerate() {
+            let from = (along / total) as f32;
+            along += start.distance(end);
+            let to = (along / total) as f32;
+
+            let normal = normals[i] * half;
+            let (start_edge, end_edge) =
+                (corner(i).unwrap_or(normal), corner(i + 1).unwrap_or(normal));
+            let mut quad = BezPath::new();
+            quad.move_to(start + start_edge);
+            quad.line_to(end + end_edge);
+            quad.line_to(end - end_edge);
+            quad.line_to(start - start_edge);
+            quad.close_path();
+
+            let stops: Vec<_> = perimeter_stops(colors, from, to, closed)
+                .into_iter()
+                .map(|(offset, color)| ColorStop {
+                    offset,
+                    color: vec_color(color),
+                })
+                .collect();
+            let gradient = Gradient::new_linear(start, end).with_stops(stops.as_slice());
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                &Brush::Gradient(gradient),
+                None,
+                &quad,
+            );
+
+            // Fill the outside of a square corner after this piece
+            let last = !looped && i + 1 == count;
+            if last || corner(i + 1).is_some() {
+                continue;
+            }
+            let after = normals[(i + 1) % count] * half;
+            let (normal, after) = match normal.cross(after) > 0.0 {
+                true => (-normal, -after),
+                false => (normal, after),
+            };
+            let mut bevel = BezPath::new();
+            bevel.move_to(end);
+            bevel.line_to(end + normal);
+            bevel.line_to(end + after);
+            bevel.close_path();
+            let color = vec_color(gradient_color(colors, to, closed));
+            scene.fill(Fill::NonZero, transform, color, None, &bevel);
+        }
+    }
+}
+
+/// Straight pieces between the points of a line, as start and end points
+fn pieces(points: &[Point], looped: bool) -> impl Iterator<Item = (Point, Point)> + '_ {
+    let count = match looped {
+        true if points.len() > 2 => points.len(),
+        _ => points.len().saturating_sub(1),
+    };
+    (0..count).map(move |i| (points[i], points[(i + 1) % points.len()]))
+}
+
+/// Gradient stops for the part of a perimeter between two fractions of the
+/// way around it, with offsets from 0 to 1 across that part
+pub(crate) fn perimeter_stops(
+    colors: &[Vec4],
+    from: f32,
+    to: f32,
+    closed: bool,
+) -> Vec<(f32, Vec4)> {
+    let mut stops = vec![(0.0, gradient_color(colors, from, closed))];
+    let count = color_spans(colors, closed);
+    for (i, color) in colors.iter().enumerate().take(count).skip(1) {
+        let at = i as f32 / count as f32;
+        if from < at && at < to {
+            stops.push(((at - from) / (to - from), *color));
+        }
+    }
+    stops.push((1.0, gradient_color(colors, to, closed)));
+    stops
+}
+
+/// Color some fraction along colors spread evenly. Closed outlines blend back
+/// into the first color at the end
+fn gradient_color(colors: &[Vec4], t: f32, closed: bool) -> Vec4 {
+    let spans = color_spans(colors, closed);
+    let position = t.clamp(0.0, 1.0) * spans as f32;
+    let i = (position as usize).min(spans - 1);
+    colors[i].lerp(colors[(i + 1) % colors.len()], position - i as f32)
+}
+
+/// Number of blends between colors spread along an outline
+fn color_spans(colors: &[Vec4], closed: bool) -> usize {
+    match closed {
+        true => colors.len(),
+        false => colors.len().saturating_sub(1).max(1),
+    }
+}
+
+fn gradient_brush(gradient: Gradient, stops: &[ColorStop], extend: Extend) -> Brush {
+    Brush::Gradient(gradient.with_extend(extend).with_stops(stops))
+}
+
+fn extend(extend: vg::Extend) -> Extend {
+    match extend {
+        vg::Extend::Pad => Extend::Pad,
+        vg::Extend::Repeat => Extend::Repeat,
+        vg::Extend::Reflect => Extend::Reflect,
+    }
+}
+
+fn scene_extend(extend: vg_scene::Extend) -> Extend {
+    match extend {
+        vg_scene::Extend::Pad => Extend::Pad,
+        vg_scene::Extend::Repeat => Extend::Repeat,
+        vg_scene::Extend::Reflect => Extend::Reflect,
+    }
+}
+
+/// Open path through all the points
+fn polyline(points: &[(f32, f32)]) -> BezPath {
+    let mut path = BezPath::new();
+    for (i, p) in points.iter().enumerate() {
+        match i {
+            0 => path.move_to(point(*p)),
+            _ => path.line_to(point(*p)),
+        }
+    }
+    path
+}
+
+fn bez_path(segments: &[PathSegment]) -> BezPath {
+    let mut path = BezPath::new();
+    for segment in segments {
+        // Kurbo requires paths to begin with a MoveTo, guests might not
+        if path.elements().is_empty() && !matches!(segment, PathSegment::MoveTo { .. }) {
+            path.move_to(Point::ZERO);
+        }
+
+        match *segment {
+            PathSegment::MoveTo { to } => path.move_to(point(to)),
+            PathSegment::LineTo { to } => path.line_to(point(to)),
+            PathSegment::QuadTo { control, to } => path.quad_to(point(control), point(to)),
+            PathSegment::CubicTo {
+                control1,
+                control2,
+                to,
+            } => path.curve_to(point(control1), point(control2), point(to)),
+            PathSegment::Close => path.close_path(),
+        }
+    }
+    path
+}
+
+fn affine(t: &Transform) -> Affine {
+    let coefficients = [
+        t.x_axis.0,
+        t.x_axis.1,
+        t.y_axis.0,
+        t.y_axis.1,
+        t.translation.0,
+        t.translation.1,
+    ];
+    Affine::new(coefficients.map(|c| c as f64))
+}
+
+/// Only the 2D part of a scene transform applies to the canvas
+fn mat_affine(m: Mat4) -> Affine {
+    let coefficients = [
+        m.x_axis.x, m.x_axis.y, m.y_axis.x, m.y_axis.y, m.w_axis.x, m.w_axis.y,
+    ];
+    Affine::new(coefficients.map(|c| c as f64))
+}
+
+fn vec_point(v: Vec2) -> Point {
+    Point::new(v.x as f64, v.y as f64)
+}
+
+fn vec_color(c: Vec4) -> Color {
+    Color::rgba(c.x as _, c.y as _, c.z as _, c.w as _)
+}
+
+fn point((x, y): (f32, f32)) -> Point {
+    Point::new(x as f64, y as f64)
+}
+
+fn color((r, g, b, a): (f32, f32, f32, f32)) -> Color {
+    Color::rgba(r as _, g as _, b as _, a as _)
+}
@@ -1,6 +1,8 @@
 //! The compositor handles management of the swapchain and compositing together
 //! the frames from <3d> and vello
 
+use vg_asset::Assets;
+use vg_interface::Viewport;
 use wgpu::*;
 use winit::{event_loop::EventLoopWindowTarget, window::WindowBuilder};
 
@@ -9,12 +11,13 @@ use crate::{
     head::{canvas::Canvas, scene::Scene},
     prelude::*,
     runtime::WorldState,
+    GuestError,
 };
 
 #[profile_all]
 impl Head {
     /// Attempt to create a new window and rendering context
-    pub async fn new(target: &EventLoopWindowTarget<()>) -> Result<Head> {
+    pub async fn new(target: &EventLoopWindowTarget<()>, assets: Arc<Assets>) -> Result<Head> {
         let window = WindowBuilder::new().with_title("VG Game").build(target)?;
         let window = Arc::new(window);
 
@@ -50,7 +53,7 @@ impl Head {
         let device = Arc::new(device);
         let queue = Arc::new(queue);
 
-        let canvas = Canvas::new(Arc::clone(&device), Arc::clone(&queue))?;
+        let canvas = Canvas::new(Arc::clone(&device), Arc::clone(&queue), assets)?;
 
         let scene = Scene::new(
             Arc::new(instance),
@@ -95,6 +98,21 @@ impl Head {
         debug!(format = ?config.format, present = ?config.present_mode, "Configured surface");
     }
 
+    /// Current window state, as games see it
+    pub fn viewport(&self) -> Viewport {
+        let size = self.window.inner_size();
+        let scale = self.window.scale_factor();
+        let logical = size.to_logical::<f32>(scale);
+
+        Viewport {
+            canvas_size: (size.width as f32, size.height as f32),
+            physical_size: (size.width as f32, size.height as f32),
+            size: (logical.width, logical.height),
+            scale: scale as f32,
+            focused: self.window.has_focus(),
+        }
+    }
+
     /// Try to get the current swapchain texture, or reconfigure if error
     fn acquire_surface(&mut self) -> Check<SurfaceTexture> {
         // TODO: Surface may be suboptimal
@@ -109,7 +127,12 @@ impl Head {
     }
 
     /// Perform all rendering
-    pub fn render_composite(&mut self, world: &WorldState) -> Nil {
+    pub fn render_composite(
+        &mut self,
+        world: &WorldState,
+        error: Option<&GuestError>,
+        debug: bool,
+    ) -> Nil {
         // We are a good citizen
         self.device.poll(Maintain::Poll);
 
@@ -117,7 +140,7 @@ impl Head {
 
         // First render 3D content, then overlay 2D content
         self.scene.render(&surface.texture);
-        self.canvas.render(&surface, world);
+        self.canvas.render(&surface, world, error, debug);
 
         // Just before presentation, this helps the OS schedule things
         self.window.pre_present_notify();
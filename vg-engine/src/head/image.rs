@@ -0,0 +1,60 @@
+//! Image loading and sprite drawing for the canvas
+
+use std::path::Path;
+
+use vg_asset::{Asset, AssetKind, Assets, ImageAsset};
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{BlendMode, Blob, Color, Compose, Fill, Format, Image, Mix},
+    Scene,
+};
+
+use crate::prelude::*;
+
+/// Image uploaded for vello
+pub struct CanvasImage(pub Image);
+
+impl AssetKind for CanvasImage {
+    type Data = Asset<ImageAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let image = data.get()?;
+        let blob = Blob::new(Arc::clone(&image.rgba) as _);
+        Some(CanvasImage(Image::new(
+            blob,
+            Format::Rgba8,
+            image.width,
+            image.height,
+        )))
+    }
+}
+
+/// Draw the `src` region of an image, with its top left corner at the origin
+/// of `transform`. The image colors are multiplied by `tint`
+pub fn draw_image(scene: &mut Scene, transform: Affine, image: &Image, src: Rect, tint: Color) {
+    let clip = Rect::from_origin_size((0.0, 0.0), src.size());
+
+    // Clip to the source region, and apply tint alpha to the whole sprite
+    let alpha = tint.a as f32 / 255.0;
+    scene.push_layer(Mix::Normal, alpha, transform, &clip);
+    scene.draw_image(image, transform * Affine::translate(-src.origin().to_vec2()));
+
+    // Multiply the colors where the image has been drawn
+    if tint.r != 255 || tint.g != 255 || tint.b != 255 {
+        let opaque = Color::rgb8(tint.r, tint.g, tint.b);
+        scene.push_layer(
+            BlendMode::new(Mix::Multiply, Compose::SrcAtop),
+            1.0,
+            transform,
+            &clip,
+        );
+        scene.fill(Fill::NonZero, transform, opaque, None, &clip);
+        scene.pop_layer();
+    }
+
+    scene.pop_layer();
+}
@@ -13,10 +13,14 @@ use winit::{
 use crate::Engine;
 
 use self::{canvas::Canvas, scene::Scene};
+#[cfg(test)]
+pub(crate) use self::canvas::perimeter_stops;
 
 mod canvas;
 mod compositor;
+mod image;
 mod scene;
+mod text;
 
 pub struct Head {
     adapter: Arc<Adapter>,
@@ -38,8 +42,11 @@ impl Engine {
     /// Create a window if the current one is closed, unless in headless mode
     pub fn ensure_window(&mut self, target: &EventLoopWindowTarget<()>) {
         if !self.config.headless && self.head.is_none() && self.between_resumes {
-            self.head = match self.block_on(Head::new(target)) {
-                Ok(w) => Some(w),
+            self.head = match self.block_on(Head::new(target, Arc::clone(&self.assets))) {
+                Ok(w) => {
+                    self.viewport = w.viewport();
+                    Some(w)
+                }
                 Err(e) => {
                     error!("Failed to create window: {e}");
                     None
@@ -61,7 +68,7 @@ impl Engine {
         // Using map style for borrowing rules
         self.head.as_mut().map(|head| {
             // This internally invokes 3D and 2D render
-            head.render_composite(&self.world);
+            head.render_composite(&self.world, self.guest_error.as_ref(), self.debug_overlay);
         });
     }
 
@@ -69,6 +76,7 @@ impl Engine {
         let head = self.head_mut()?;
 
         head.configure();
+        self.viewport = head.viewport();
 
         debug!(width = size.width, height = size.height, "Resized window");
         Nil
@@ -0,0 +1,143 @@
+//! Font loading, shaping and simple text layout for the canvas
+
+use std::{cell::RefCell, path::Path};
+
+use vg_asset::{Asset, AssetKind, Assets, FontAsset};
+use vg_interface::Align;
+use swash::{
+    shape::{cluster::Glyph as ShapedGlyph, ShapeContext},
+    text::{Codepoint, Script},
+    FontRef as ShapeFont,
+};
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Brush, Fill, Font},
+    skrifa::{
+        instance::{LocationRef, Size},
+        raw::{FileRef, FontRef},
+        MetadataProvider,
+    },
+    Scene,
+};
+
+use crate::prelude::*;
+
+/// Font that is always available, even without any assets
+static DEFAULT_FONT: &[u8] = include_bytes!("../../assets/DejaVuSansMono.ttf");
+
+thread_local! {
+    /// Reused between texts to keep its caches
+    static SHAPER: RefCell<ShapeContext> = RefCell::new(ShapeContext::new());
+}
+
+/// Font uploaded for vello
+pub struct CanvasFont(pub Font);
+
+impl CanvasFont {
+    pub fn default_font() -> CanvasFont {
+        CanvasFont(Font::new(Blob::new(Arc::new(DEFAULT_FONT)), 0))
+    }
+}
+
+impl AssetKind for CanvasFont {
+    type Data = Asset<FontAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let font = data.get()?;
+        let blob = Blob::new(Arc::clone(&font.bytes) as _);
+        Some(CanvasFont(Font::new(blob, 0)))
+    }
+}
+
+/// Parse the font tables, picking the right font if this is a collection
+fn font_ref(font: &Font) -> Option<FontRef<'_>> {
+    match FileRef::new(font.data.as_ref()).ok()? {
+        FileRef::Font(font) => Some(font),
+        FileRef::Collection(collection) => collection.get(font.index).ok(),
+    }
+}
+
+/// Lay out and draw text. The origin of `transform` is the top edge of the
+/// first line, horizontally anchored by `align`
+pub fn draw_text(
+    scene: &mut Scene,
+    transform: Affine,
+    font: &Font,
+    text: &str,
+    size: f32,
+    brush: &Brush,
+    align: Align,
+) {
+    let Some(font_ref) = font_ref(font) else {
+        return;
+    };
+    let Some(shape_font) = ShapeFont::from_index(font.data.as_ref(), font.index as usize) else {
+        return;
+    };
+
+    let metrics = font_ref.metrics(Size::new(size), LocationRef::default());
+    let line_height = metrics.ascent - metrics.descent + metrics.leading;
+
+    let mut glyphs = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = SHAPER.with_borrow_mut(|shaper| shape(shaper, shape_font, line, size));
+        let width: f32 = line.iter().map(|glyph| glyph.advance).sum();
+
+        let mut x = match align {
+            Align::Left => 0.0,
+            Align::Center => -width / 2.0,
+            Align::Right => -width,
+        };
+        let y = metrics.ascent + i as f32 * line_height;
+
+        for glyph in line {
+            glyphs.push(Glyph {
+                id: glyph.id as u32,
+                x: x + glyph.x,
+                // Font offsets point up
+                y: y - glyph.y,
+            });
+            x += glyph.advance;
+        }
+    }
+
+    scene
+        .draw_glyphs(font)
+        .font_size(size)
+        .transform(transform)
+        .brush(brush)
+        .draw(Fill::NonZero, glyphs.into_iter());
+}
+
+/// Shape a line as one run in the script of its first letter, applying
+/// kerning, ligatures and joining forms. Glyphs come out left to right
+fn shape(shaper: &mut ShapeContext, font: ShapeFont, line: &str, size: f32) -> Vec<ShapedGlyph> {
+    let script = line
+        .chars()
+        .map(|c| c.script())
+        .find(|s| !matches!(s, Script::Common | Script::Inherited | Script::Unknown))
+        .unwrap_or(Script::Latin);
+
+    let mut clusters = vec![];
+    let mut run = shaper.builder(font).script(script).size(size).build();
+    run.add_str(line);
+    run.shape_with(|cluster| clusters.push(cluster.glyphs.to_vec()));
+
+    // Shaping keeps the text order, so lay right-to-left scripts out backwards
+    if right_to_left(script) {
+        clusters.reverse();
+    }
+    clusters.concat()
+}
+
+fn right_to_left(script: Script) -> bool {
+    matches!(
+        script,
+        Script::Arabic | Script::Hebrew | Script::Syriac | Script::Thaana | Script::Nko
+    )
+}
@@ -0,0 +1,317 @@
+//! Collects window input between ticks
+
+use vg_interface::{Gamepad, Input, Key, MouseButton, PadAxis, PadButton, Touch, TouchPhase};
+use winit::{
+    event::{ElementState, MouseScrollDelta, WindowEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+/// Approximate height of a scrolled line, for touchpads reporting pixels
+const PIXELS_PER_LINE: f32 = 16.0;
+
+/// Live input state, turned into an `Input` snapshot for every tick
+#[derive(Default)]
+pub struct InputState {
+    current: Input,
+    /// Backend identifiers of gamepads, by their stable index
+    pad_ids: Vec<usize>,
+}
+
+/// Gamepad event, independent of the backend it came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PadEvent {
+    Connected,
+    Disconnected,
+    Pressed(PadButton),
+    Released(PadButton),
+    Axis(PadAxis, f32),
+}
+
+impl InputState {
+    /// Record a window event, if it is input related
+    pub fn event(&mut self, event: &WindowEvent) {
+        let input = &mut self.current;
+
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                let PhysicalKey::Code(code) = event.physical_key else {
+                    return;
+                };
+                let Some(key) = map_key(code) else { return };
+
+                match event.state {
+                    // Don't count OS key repeats as new presses
+                    ElementState::Pressed if !event.repeat => {
+                        press(&mut input.keys_down, &mut input.keys_pressed, key)
+                    }
+                    ElementState::Pressed => (),
+                    ElementState::Released => {
+                        release(&mut input.keys_down, &mut input.keys_released, key)
+                    }
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let Some(button) = map_button(*button) else {
+                    return;
+                };
+
+                match state {
+                    ElementState::Pressed => {
+                        press(&mut input.buttons_down, &mut input.buttons_pressed, button)
+                    }
+                    ElementState::Released => {
+                        release(&mut input.buttons_down, &mut input.buttons_released, button)
+                    }
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                input.cursor = Some((position.x as f32, position.y as f32));
+            }
+            WindowEvent::CursorLeft { .. } => input.cursor = None,
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (*x, *y),
+                    MouseScrollDelta::PixelDelta(p) => {
+                        (p.x as f32 / PIXELS_PER_LINE, p.y as f32 / PIXELS_PER_LINE)
+                    }
+                };
+                input.wheel.0 += x;
+                input.wheel.1 += y;
+            }
+            WindowEvent::Touch(touch) => {
+                let position = (touch.location.x as f32, touch.location.y as f32);
+                let phase = match touch.phase {
+                    winit::event::TouchPhase::Started => TouchPhase::Started,
+                    winit::event::TouchPhase::Moved => TouchPhase::Moved,
+                    winit::event::TouchPhase::Ended => TouchPhase::Ended,
+                    winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+                };
+                touch_event(&mut input.touches, touch.id, phase, position);
+            }
+            // Release everything, we won't hear about keys let go elsewhere
+            WindowEvent::Focused(false) => {
+                for key in std::mem::take(&mut input.keys_down) {
+                    input.keys_released.push(key);
+                }
+                for button in std::mem::take(&mut input.buttons_down) {
+                    input.buttons_released.push(button);
+                }
+                for touch in &mut input.touches {
+                    if touch.phase.is_active() {
+                        touch.phase = TouchPhase::Cancelled;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Record an event of a gamepad. `id` identifies the pad to the backend,
+    /// and is mapped to an index that stays the same across reconnects
+    pub fn pad_event(&mut self, id: usize, event: PadEvent) {
+        let index = match self.pad_ids.iter().position(|&i| i == id) {
+            Some(index) => index,
+            None => {
+                let index = self.pad_ids.len();
+                self.pad_ids.push(id);
+                self.current.gamepads.push(Gamepad {
+                    index: index as u32,
+                    ..Default::default()
+                });
+                index
+            }
+        };
+        let pad = &mut self.current.gamepads[index];
+
+        match event {
+            PadEvent::Connected if !pad.connected => {
+                pad.connected = true;
+                pad.connection_changed = true;
+            }
+            PadEvent::Connected => (),
+            // Nothing is held on a pad that is gone
+            PadEvent::Disconnected if pad.connected => {
+                pad.connected = false;
+                pad.connection_changed = true;
+                for button in std::mem::take(&mut pad.buttons_down) {
+                    pad.buttons_released.push(button);
+                }
+                pad.axes.clear();
+            }
+            PadEvent::Disconnected => (),
+            PadEvent::Pressed(button) => {
+                press(&mut pad.buttons_down, &mut pad.buttons_pressed, button)
+            }
+            PadEvent::Released(button) => {
+                release(&mut pad.buttons_down, &mut pad.buttons_released, button)
+            }
+            PadEvent::Axis(axis, value) => {
+                pad.axes.retain(|(a, _)| *a != axis);
+                if value != 0.0 {
+                    pad.axes.push((axis, value.clamp(-1.0, 1.0)));
+                }
+            }
+        }
+    }
+
+    /// Take the input for a new tick, clearing everything that only applies
+    /// to the time since the previous tick
+    pub fn snapshot(&mut self) -> Input {
+        let snapshot = self.current.clone();
+
+        self.current.keys_pressed.clear();
+        self.current.keys_released.clear();
+        self.current.buttons_pressed.clear();
+        self.current.buttons_released.clear();
+        self.current.wheel = (0.0, 0.0);
+        self.current.touches.retain(|touch| touch.phase.is_active());
+        for touch in &mut self.current.touches {
+            touch.phase = TouchPhase::Stationary;
+        }
+        for pad in &mut self.current.gamepads {
+            pad.buttons_pressed.clear();
+            pad.buttons_released.clear();
+            pad.connection_changed = false;
+        }
+
+        snapshot
+    }
+}
+
+/// Update a touch, keeping the phase of the tick meaningful. A touch that
+/// started this tick stays started even if it also moved
+fn touch_event(touches: &mut Vec<Touch>, id: u64, phase: TouchPhase, position: (f32, f32)) {
+    let existing = touches
+        .iter_mut()
+        .find(|touch| touch.id == id && touch.phase.is_active());
+
+    match (existing, phase) {
+        (Some(touch), TouchPhase::Moved) => {
+            touch.position = position;
+            if touch.phase != TouchPhase::Started {
+                touch.phase = TouchPhase::Moved;
+            }
+        }
+        (Some(touch), phase) => {
+            touch.position = position;
+            touch.phase = phase;
+        }
+        // Lost the start of the touch, but it is still worth reporting
+        (None, phase) => touches.push(Touch {
+            id,
+            phase,
+            position,
+        }),
+    }
+}
+
+fn press<T: PartialEq + Copy>(down: &mut Vec<T>, pressed: &mut Vec<T>, value: T) {
+    if !down.contains(&value) {
+        down.push(value);
+    }
+    pressed.push(value);
+}
+
+fn release<T: PartialEq>(down: &mut Vec<T>, released: &mut Vec<T>, value: T) {
+    down.retain(|v| *v != value);
+    released.push(value);
+}
+
+fn map_button(button: winit::event::MouseButton) -> Option<MouseButton> {
+    use winit::event::MouseButton as B;
+
+    Some(match button {
+        B::Left => MouseButton::Left,
+        B::Right => MouseButton::Right,
+        B::Middle => MouseButton::Middle,
+        B::Back => MouseButton::Back,
+        B::Forward => MouseButton::Forward,
+        B::Other(_) => return None,
+    })
+}
+
+fn map_key(code: KeyCode) -> Option<Key> {
+    Some(match code {
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyB => Key::B,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyD => Key::D,
+        KeyCode::KeyE => Key::E,
+        KeyCode::KeyF => Key::F,
+        KeyCode::KeyG => Key::G,
+        KeyCode::KeyH => Key::H,
+        KeyCode::KeyI => Key::I,
+        KeyCode::KeyJ => Key::J,
+        KeyCode::KeyK => Key::K,
+        KeyCode::KeyL => Key::L,
+        KeyCode::KeyM => Key::M,
+        KeyCode::KeyN => Key::N,
+        KeyCode::KeyO => Key::O,
+        KeyCode::KeyP => Key::P,
+        KeyCode::KeyQ => Key::Q,
+        KeyCode::KeyR => Key::R,
+        KeyCode::KeyS => Key::S,
+        KeyCode::KeyT => Key::T,
+        KeyCode::KeyU => Key::U,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyW => Key::W,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+        KeyCode::Digit0 => Key::Digit0,
+        KeyCode::Digit1 => Key::Digit1,
+        KeyCode::Digit2 => Key::Digit2,
+        KeyCode::Digit3 => Key::Digit3,
+        KeyCode::Digit4 => Key::Digit4,
+        KeyCode::Digit5 => Key::Digit5,
+        KeyCode::Digit6 => Key::Digit6,
+        KeyCode::Digit7 => Key::Digit7,
+        KeyCode::Digit8 => Key::Digit8,
+        KeyCode::Digit9 => Key::Digit9,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        KeyCode::ArrowUp => Key::Up,
+        KeyCode::ArrowDown => Key::Down,
+        KeyCode::ArrowLeft => Key::Left,
+        KeyCode::ArrowRight => Key::Right,
+        KeyCode::Space => Key::Space,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::ShiftLeft => Key::LeftShift,
+        KeyCode::ShiftRight => Key::RightShift,
+        KeyCode::ControlLeft => Key::LeftControl,
+        KeyCode::ControlRight => Key::RightControl,
+        KeyCode::AltLeft => Key::LeftAlt,
+        KeyCode::AltRight => Key::RightAlt,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::Equal => Key::Equal,
+        KeyCode::Comma => Key::Comma,
+        KeyCode::Period => Key::Period,
+        KeyCode::Slash => Key::Slash,
+        KeyCode::Backslash => Key::Backslash,
+        KeyCode::Semicolon => Key::Semicolon,
+        KeyCode::Quote => Key::Quote,
+        KeyCode::Backquote => Key::Backquote,
+        KeyCode::BracketLeft => Key::LeftBracket,
+        KeyCode::BracketRight => Key::RightBracket,
+        _ => return None,
+    })
+}
@@ -1,26 +1,44 @@
 #![feature(try_trait_v2)]
-#![feature(array_windows)]
 #![allow(non_local_definitions)]
 
-use runtime::WorldState;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use audio::{AudioOutput, Mixer};
+use gamepad::Gamepads;
+use input::InputState;
+use runtime::{SharedBytes, TickInput, WorldState};
+use storage::Storage;
 use vg_asset::{Asset, Assets};
-use vg_runtime::executor::WasmInstance;
+use vg_interface::Viewport;
+use vg_runtime::executor::{Loaded, WasmInstance};
 use winit::{
     event::{Event, WindowEvent},
     event_loop::EventLoopWindowTarget,
+    keyboard::{KeyCode, PhysicalKey},
 };
 
+mod audio;
 mod check;
+mod gamepad;
 mod head;
+mod input;
 mod platform;
 mod prelude;
 mod runtime;
+mod scaling;
+mod storage;
+#[cfg(test)]
+mod test;
 
 pub(crate) use prelude::*;
 
 use head::Head;
 
 pub use runtime::{RuntimeInstant, SaveState};
+pub use vg_runtime::executor::{GuestError, GuestFrame};
 
 pub struct Engine {
     config: EngineConfig,
@@ -33,11 +51,39 @@ pub struct Engine {
     /// Asset server
     assets: Arc<Assets>,
     /// Game logic instance
-    instance: Asset<WasmInstance>,
+    instance: Asset<Loaded<WasmInstance>>,
+    /// Why the game module couldn't be loaded, until it changes
+    load_error: Option<String>,
+    /// Files the game has loaded, by path
+    asset_loads: HashMap<String, Asset<SharedBytes>>,
     /// Current engine time
     instant: RuntimeInstant,
+    /// Random seed of the session
+    seed: u64,
     /// Most recently calculate world state
     world: WorldState,
+    /// Input collected since the last tick
+    input: InputState,
+    gamepads: Gamepads,
+    /// Latest window state
+    viewport: Viewport,
+    /// Input given to simulated ticks, replayed when re-simulating. Only ticks
+    /// that can still be rolled back are kept, unless configured otherwise
+    input_log: BTreeMap<RuntimeInstant, TickInput>,
+    /// Sounds played by the game
+    mixer: Arc<Mutex<Mixer>>,
+    /// Feeds the mixer to the audio device, unless headless
+    audio_output: Option<AudioOutput>,
+    /// Save data of the game
+    storage: Storage,
+    /// Tags game log messages
+    game_name: String,
+    /// Set when the game crashes, stopping it from ticking
+    guest_error: Option<GuestError>,
+    /// Set when the game exits
+    exit_code: Option<i32>,
+    /// Show the gizmos of the game
+    debug_overlay: bool,
 }
 
 #[derive(Clone)]
@@ -50,6 +96,22 @@ pub struct EngineConfig {
     pub signaling: String,
     /// Room to connect to, if networking is used
     pub room: Option<String>,
+    /// Simulated ticks per second, which sets the time step games see
+    pub tick_rate: u32,
+    /// Random seed given to the game. Clients sharing a session must use the
+    /// same seed. Picked at random if None
+    pub seed: Option<u64>,
+    /// Identifies the game for its save data. Named after the game binary if
+    /// None
+    pub game_id: Option<String>,
+    /// How many ticks back the engine may be restored. Side effects of a tick
+    /// like storage writes are committed once it is this old
+    pub rollback_ticks: usize,
+    /// Keep the input of every tick since the start, instead of only those
+    /// that can still be rolled back. Lets a whole session be re-simulated,
+    /// so side effects are only committed by `Engine::confirm_before` or when
+    /// the engine goes away
+    pub keep_input_log: bool,
 }
 
 impl EngineConfig {
@@ -60,10 +122,35 @@ impl EngineConfig {
             path: "target/wasm32-wasi/debug/my-game.wasm".into(),
             signaling: "ws://vg.noxim.xyz:3536/".into(),
             room: None,
+            tick_rate: 60,
+            seed: None,
+            game_id: None,
+            rollback_ticks: 600,
+            keep_input_log: false,
+        }
+    }
+
+    /// The game id, or the name of the game binary
+    pub fn game_name(&self) -> String {
+        match &self.game_id {
+            Some(id) => id.clone(),
+            None => std::path::Path::new(&self.path)
+                .file_stem()
+                .map_or("game".into(), |stem| stem.to_string_lossy().into()),
         }
     }
 }
 
+/// Seed for sessions that don't specify one
+fn random_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    // Std already gathers randomness for hash maps
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
 /// Some platforms don't have proper Resumed/Suspended lifecycles. Important for
 /// when using an external event loop
 fn has_app_lifecycle() -> bool {
@@ -79,13 +166,36 @@ impl Engine {
     /// Create a new engine with specified configuration
     pub fn with_config(config: EngineConfig) -> Engine {
         let assets = Assets::new();
+
+        let mixer = Arc::new(Mutex::new(Mixer::new(Arc::clone(&assets), config.tick_rate)));
+        let audio_output = match config.headless {
+            true => None,
+            false => AudioOutput::new(Arc::clone(&mixer))
+                .map_err(|e| error!("Audio initialization error: {e}"))
+                .ok(),
+        };
+
         Engine {
             head: None,
             alive: true,
             between_resumes: !has_app_lifecycle(),
             instance: assets.get(&config.path),
+            load_error: None,
+            asset_loads: HashMap::new(),
             instant: RuntimeInstant::EPOCH,
+            seed: config.seed.unwrap_or_else(random_seed),
             world: Default::default(),
+            input: Default::default(),
+            gamepads: Gamepads::new(),
+            viewport: Viewport::default(),
+            input_log: BTreeMap::new(),
+            mixer,
+            audio_output,
+            storage: Storage::new(Storage::game_dir(&config)),
+            game_name: config.game_name(),
+            guest_error: None,
+            exit_code: None,
+            debug_overlay: false,
             assets,
             config,
         }
@@ -102,6 +212,32 @@ impl Engine {
         &self.assets
     }
 
+    /// Mix the sounds of the game into interleaved stereo samples at
+    /// `audio_sample_rate`. Meant for headless mode, otherwise the audio device
+    /// is already taking samples from the same mixer
+    pub fn mix_audio(&self, out: &mut [f32]) {
+        self.mixer.lock().unwrap().mix(out);
+    }
+
+    pub fn audio_sample_rate(&self) -> u32 {
+        self.mixer.lock().unwrap().sample_rate()
+    }
+
+    /// Are the debug gizmos of the game shown. Toggled with F12 in debug
+    /// builds
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    pub fn set_debug_overlay(&mut self, visible: bool) {
+        self.debug_overlay = visible;
+    }
+
+    /// Is sound being played on an audio device
+    pub fn has_audio_output(&self) -> bool {
+        self.audio_output.is_some()
+    }
+
     /// Process a winit event
     #[profile]
     pub fn event(&mut self, event: &Event<()>, target: &EventLoopWindowTarget<()>) -> Nil {
@@ -124,7 +260,21 @@ impl Engine {
                     self.render();
                     profiling::finish_frame!();
                 }
-                _ => (),
+                WindowEvent::KeyboardInput { event: key, .. } => {
+                    // Developer shortcut, the game sees the key as well
+                    let toggle = key.physical_key == PhysicalKey::Code(KeyCode::F12)
+                        && key.state.is_pressed()
+                        && !key.repeat;
+                    if cfg!(debug_assertions) && toggle {
+                        self.debug_overlay = !self.debug_overlay;
+                    }
+                    self.input.event(event);
+                }
+                WindowEvent::Focused(focused) => {
+                    self.viewport.focused = *focused;
+                    self.input.event(event);
+                }
+                event => self.input.event(event),
             },
             Event::AboutToWait => {
                 self.redraw();
@@ -143,10 +293,10 @@ impl Engine {
     #[profile]
     pub fn poll(&mut self) -> PollResult {
         check::check_default(|| {
-            // TODO: Tick rate
-            self.run_tick()?;
+            self.gamepads.poll(&mut self.input);
 
-            Check::Pass(PollResult::Tick {})
+            // TODO: Tick rate
+            self.run_tick()
         })
     }
 
@@ -189,6 +339,8 @@ pub enum PollResult {
     None,
     /// A tick has occurred
     Tick,
+    /// The game has exited and won't tick anymore
+    Exited { code: i32 },
 }
 
 /*
@@ -1,12 +1,17 @@
 //! Platform level functionality, like event loop
 //! Note that desktop.rs/android.rs/etc.rs are not part of this module
 
+use std::time::{Duration, Instant};
+
 use winit::{
     event::Event,
     event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
 };
 
-use crate::Engine;
+use crate::{prelude::*, Engine, PollResult};
+
+/// How long a headless run waits for the game module to arrive
+const LOAD_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[cfg(target_os = "android")]
 include!("android.rs");
@@ -19,6 +24,33 @@ impl Engine {
             .expect("Event loop exited")
     }
 
+    /// Run the game without a window until it exits, returning its exit code.
+    /// Fails if the game crashes, can't be loaded, or doesn't arrive in time
+    pub fn run_headless(mut self) -> Result<i32> {
+        // Reset on every tick, as hot reloads unload the game for a moment
+        let mut waiting_since = Instant::now();
+        loop {
+            match self.poll() {
+                PollResult::Exited { code } => return Ok(code),
+                PollResult::Tick => waiting_since = Instant::now(),
+                PollResult::None => {
+                    if let Some(e) = self.guest_error() {
+                        return Err(e.clone().into());
+                    }
+                    if let Some(e) = self.load_error() {
+                        return Err(anyhow!("Failed to load game: {e}"));
+                    }
+
+                    // Game is still loading
+                    if waiting_since.elapsed() > LOAD_TIMEOUT {
+                        return Err(anyhow!("Game {} did not load", self.config.path));
+                    }
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+    }
+
     /// Convenience function for typical winit event receiver
     pub fn event_handler(mut self) -> impl FnMut(Event<()>, &EventLoopWindowTarget<()>) {
         move |event, target| {
@@ -4,7 +4,7 @@
 pub use crate::check::{Check, Nil, FAIL, PASS};
 pub use anyhow::{anyhow, Result};
 pub use tracing::{debug, error, info, log, trace};
-pub use glam::{Vec2, UVec2, Vec3, Vec4};
+pub use glam::{Mat4, Vec2, UVec2, Vec3, Vec4};
 pub use profiling::{function as profile, all_functions as profile_all, scope as profile_scope};
 
 pub use std::sync::Arc;
@@ -1,13 +1,25 @@
-use std::fmt::Display;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    path::Path,
+};
 
-use vg_interface::{Draw, Request, Response};
+use vg_asset::{Asset, AssetKind, Assets, BinAsset};
+use vg_interface::{
+    Camera, Draw, Extend, Input, LogLevel, OutlinePaint, Paint, Request, Resolution, Response,
+    SceneObject, Shape, Space, Time, Transform, Viewport,
+};
 use vg_runtime::{
-    executor::{Instance, InstanceData},
+    executor::{GuestError, Instance, InstanceData, Loaded},
     Provider,
 };
+use vg_scene::Scene;
 
+use crate::audio::Sound;
 use crate::prelude::*;
-use crate::Engine;
+use crate::scaling::Placement;
+use crate::storage::Storage;
+use crate::{Engine, PollResult};
 
 /// Represents a point in "time" for the game
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
@@ -45,75 +57,595 @@ impl Display for RuntimeInstant {
 
 #[profile_all]
 impl Engine {
-    /// Run the instance until a new frame is ready
-    pub(crate) fn run_tick(&mut self) -> Check {
+    /// Run the instance until a new frame is ready, or the game exits
+    pub(crate) fn run_tick(&mut self) -> Check<PollResult> {
         // Done before check to keep asset loading active
-        let instance = Check::from(self.instance.get())?;
+        let instance = match Check::from(self.instance.get())? {
+            Loaded::Ready(instance) => {
+                self.load_error = None;
+                instance
+            }
+            Loaded::Failed(error) => {
+                self.load_error = Some(error.clone());
+                return Check::Fail;
+            }
+        };
+
+        // A crashed game stays stopped until restored to a good state
+        Check::from(self.guest_error.is_none())?;
+        Check::from(self.exit_code.is_none())?;
+
+        // Input is only sampled the first time a tick is simulated, so
+        // re-simulating after a restore sees exactly the same input
+        let resimulating = self.input_log.contains_key(&self.instant);
+        let TickInput {
+            input,
+            viewport,
+            assets,
+            storage_reads,
+        } = self
+            .input_log
+            .entry(self.instant)
+            .or_insert_with(|| TickInput {
+                input: self.input.snapshot(),
+                viewport: self.viewport,
+                assets: BTreeMap::new(),
+                storage_reads: vec![],
+            });
+
+        let delta = 1.0 / self.config.tick_rate as f64;
+        let tick = self.instant.frame as u64;
+        let time = Time {
+            tick,
+            delta: delta as f32,
+            elapsed: tick as f64 * delta,
+        };
 
-        // Record things
-        let mut world = WorldState::default();
+        // Record things, starting from the settings of the previous tick
+        let mut world = self.world.next();
+        let mut provider = TickProvider {
+            instant: self.instant,
+            resimulating,
+            game: &self.game_name,
+            input,
+            viewport: *viewport,
+            time,
+            seed: self.seed,
+            world: &mut world,
+            sounds: vec![],
+            storage: &mut self.storage,
+            storage_reads,
+            reads_answered: 0,
+            exit_code: None,
+            asset_server: &self.assets,
+            asset_loads: &mut self.asset_loads,
+            assets,
+        };
+
+        // Run until frame is ready, or the game waits on something
+        let reason = loop {
+            match instance.step(&mut provider) {
+                Ok(reason) if reason.ends_tick() => break reason,
+                // Writes of the final tick are kept, and committed when the
+                // engine goes away
+                Ok(reason) if reason.is_exit() => {
+                    let code = provider.exit_code.unwrap_or(0);
+                    info!(game = self.game_name, instant = %self.instant, code, "Game exited");
+
+                    self.exit_code = Some(code);
+                    self.alive = false;
+                    return Check::Pass(PollResult::Exited { code });
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    error!(game = self.game_name, instant = %self.instant, "Game crashed: {e}");
 
-        // Run until frame is ready
-        while !instance.step(&mut world).is_present() {}
+                    // Side effects of the failed tick never happened
+                    self.storage.rewind(self.instant);
+                    self.guest_error = Some(e);
+                    return Check::Fail;
+                }
+            }
+        };
+
+        let sounds = provider.sounds;
+        self.mixer.lock().unwrap().tick(self.instant, sounds);
         self.instant.frame += 1;
 
-        // Update the presentation world
+        // Old enough that it can't be rolled back. With the full log kept,
+        // the owner of the engine decides what is final
+        let oldest = self.instant.frame.checked_sub(self.config.rollback_ticks);
+        if let Some(frame) = oldest.filter(|_| !self.config.keep_input_log) {
+            let oldest = RuntimeInstant { frame };
+            self.storage.confirm_before(oldest);
+
+            while let Some(entry) = self.input_log.first_entry() {
+                if *entry.key() >= oldest {
+                    break;
+                }
+                entry.remove();
+            }
+        }
+
+        // Update the presentation world. A pending tick has nothing new to
+        // show, so the last frame stays up
+        if reason.is_pending() {
+            world.draws = std::mem::take(&mut self.world.draws);
+            world.debug_draws = std::mem::take(&mut self.world.debug_draws);
+        }
         self.world = world;
         self.redraw();
 
-        PASS
+        Check::Pass(PollResult::Tick)
     }
 
     /// Produce a save state from the current state, which can be used to restore
     pub fn save_state(&mut self) -> Option<SaveState> {
-        let instance = self.instance.get()?;
+        let Loaded::Ready(instance) = self.instance.get()? else {
+            return None;
+        };
 
         let data = instance.get_data();
 
         Some(SaveState {
             data,
             instant: self.instant,
+            seed: self.seed,
+            world: self.world.clone(),
         })
     }
 
     /// Set the instance state to some premade save state
     pub fn restore_state(&mut self, save_state: &SaveState) -> Result<()> {
-        let instance = self.instance.get().ok_or(anyhow!("What"))?;
+        let Some(Loaded::Ready(instance)) = self.instance.get() else {
+            return Err(anyhow!("The game is not loaded"));
+        };
 
         instance.set_data(&save_state.data);
         self.instant = save_state.instant;
+        self.seed = save_state.seed;
+        self.world = save_state.world.clone();
+        self.mixer.lock().unwrap().rewind(self.instant);
+        self.storage.rewind(self.instant);
+        self.guest_error = None;
+        // Going back to before an exit brings the session back to life
+        self.exit_code = None;
+        self.alive = true;
 
         Ok(())
     }
 
+    /// Commit side effects like storage writes of the ticks before `instant`.
+    /// Only needed with `keep_input_log`, where nothing is committed until
+    /// then or until the engine goes away. Restoring before it afterwards
+    /// doesn't undo them
+    pub fn confirm_before(&mut self, instant: RuntimeInstant) {
+        self.storage.confirm_before(instant);
+    }
+
     pub fn runtime_instant(&self) -> RuntimeInstant {
         self.instant
     }
+
+    /// Error that stopped the game. It stays stopped until a save state is
+    /// restored
+    pub fn guest_error(&self) -> Option<&GuestError> {
+        self.guest_error.as_ref()
+    }
+
+    /// Why the game couldn't be loaded. It is loaded again once its module
+    /// changes
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    /// Exit code of the game, once it has exited
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
 }
 
 pub struct SaveState {
     data: InstanceData,
     instant: RuntimeInstant,
+    /// Random seed of the session, games may have derived any state from it
+    seed: u64,
+    /// Last presented world, which also carries settings into the next tick
+    world: WorldState,
 }
 
 impl SaveState {
     pub fn instant(&self) -> RuntimeInstant {
         self.instant
     }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 }
 
 #[derive(Default, Clone)]
 pub struct WorldState {
-    pub draws: Vec<Draw>,
+    pub draws: Vec<DrawCall>,
+    /// Gizmos of the game, shown only with the debug overlay on
+    pub debug_draws: Vec<DrawCall>,
+    /// Virtual resolution chosen by the game
+    pub resolution: Option<Resolution>,
+    /// Retained objects. Shared with older worlds until a tick changes it
+    pub scene: Arc<Scene>,
+    /// Cameras world space is seen through, chosen by the game
+    pub cameras: Vec<Camera>,
+}
+
+impl WorldState {
+    /// Empty world for the following tick, keeping settings that persist
+    pub(crate) fn next(&self) -> WorldState {
+        WorldState {
+            draws: vec![],
+            debug_draws: vec![],
+            resolution: self.resolution,
+            scene: Arc::clone(&self.scene),
+            cameras: self.cameras.clone(),
+        }
+    }
+
+    /// How the canvas is fitted onto a surface
+    pub fn placement(&self, surface: Vec2) -> Placement {
+        Placement::new(surface, self.resolution.as_ref())
+    }
+}
+
+/// A draw request along with the guest transform and space at the time
+#[derive(Clone)]
+pub struct DrawCall {
+    pub transform: Transform,
+    pub space: Space,
+    pub draw: Draw,
+}
+
+/// Everything from outside the game that a tick can observe
+pub struct TickInput {
+    pub input: Input,
+    pub viewport: Viewport,
+    /// Asset contents given to the game, by path. None if not loaded yet.
+    /// Ticks loading the same contents share them
+    pub assets: BTreeMap<String, Option<Arc<[u8]>>>,
+    /// Storage keys read by the game and their values, in order
+    pub storage_reads: Vec<(String, Option<Vec<u8>>)>,
+}
+
+/// File contents, shared until the file changes
+pub struct SharedBytes(pub Arc<[u8]>);
+
+impl AssetKind for SharedBytes {
+    type Data = Asset<BinAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        Some(SharedBytes(data.get()?.bytes.as_slice().into()))
+    }
+}
+
+/// Answers game requests during a single tick
+struct TickProvider<'a> {
+    instant: RuntimeInstant,
+    /// The tick has run before, so its side effects have already been seen
+    resimulating: bool,
+    game: &'a str,
+    input: &'a Input,
+    viewport: Viewport,
+    time: Time,
+    seed: u64,
+    world: &'a mut WorldState,
+    /// Sounds started this tick, in order
+    sounds: Vec<Sound>,
+    storage: &'a mut Storage,
+    /// Storage answers of this tick. Filled on the first simulation
+    storage_reads: &'a mut Vec<(String, Option<Vec<u8>>)>,
+    /// How many of `storage_reads` the game has been given
+    reads_answered: usize,
+    /// Set if the game asked to exit
+    exit_code: Option<i32>,
+    asset_server: &'a Arc<Assets>,
+    /// Files the game has asked for, kept loaded and hot reloading
+    asset_loads: &'a mut HashMap<String, Asset<SharedBytes>>,
+    /// Asset answers of this tick. Filled on the first simulation
+    assets: &'a mut BTreeMap<String, Option<Arc<[u8]>>>,
+}
+
+impl TickProvider<'_> {
+    fn placement(&self) -> Placement {
+        self.world.placement(self.viewport.physical_size.into())
+    }
+
+    /// Contents of an asset for the game. Answered from the log when
+    /// re-simulating, so the game sees what it saw the first time
+    fn load_asset(&mut self, path: String) -> Option<Arc<[u8]>> {
+        let TickProvider {
+            asset_server,
+            asset_loads,
+            assets,
+            ..
+        } = self;
+
+        assets
+            .entry(path)
+            .or_insert_with_key(|path| {
+                let asset = asset_loads
+                    .entry(path.clone())
+                    .or_insert_with(|| asset_server.get(path));
+                asset.get().map(|bytes| Arc::clone(&bytes.0))
+            })
+            .clone()
+    }
+
+    /// Value of a storage key for the game. Answered from the log when
+    /// re-simulating, so the game doesn't see writes or files changed since
+    fn read_storage(&mut self, key: String) -> Option<Vec<u8>> {
+        let logged = self.storage_reads.get(self.reads_answered);
+        let bytes = match logged.filter(|(logged, _)| *logged == key) {
+            Some((_, bytes)) => bytes.clone(),
+            // Read for real the first time, or if the game has changed since
+            // and asks for something else
+            None => {
+                self.storage_reads.truncate(self.reads_answered);
+                let bytes = self.storage.read(&key);
+                self.storage_reads.push((key, bytes.clone()));
+                bytes
+            }
+        };
+        self.reads_answered += 1;
+        bytes
+    }
 }
 
 #[profile_all]
-impl Provider for WorldState {
+impl Provider for TickProvider<'_> {
     fn provide(&mut self, request: Request) -> Response {
         match request {
-            Request::Draw(draw) => self.draws.push(draw),
+            Request::Draw {
+                transform,
+                space,
+                draw,
+            } => {
+                self.world.draws.push(DrawCall {
+                    transform,
+                    space,
+                    draw,
+                });
+            }
+            Request::DebugDraw {
+                transform,
+                space,
+                draw,
+            } => {
+                // Never shown in release builds, so not worth keeping
+                if cfg!(debug_assertions) {
+                    self.world.debug_draws.push(DrawCall {
+                        transform,
+                        space,
+                        draw,
+                    });
+                }
+            }
+            Request::Input => {
+                // Positions are reported in canvas units
+                let placement = self.placement();
+                let mut input = self.input.clone();
+                input.cursor = input.cursor.map(|p| placement.to_virtual(p));
+                for touch in &mut input.touches {
+                    touch.position = placement.to_virtual(touch.position);
+                }
+                return Response::Input(input);
+            }
+            Request::Time => return Response::Time(self.time),
+            Request::Seed => return Response::Seed(self.seed),
+            Request::Viewport => {
+                let visible = self.placement().visible;
+                return Response::Viewport(Viewport {
+                    canvas_size: visible.into(),
+                    ..self.viewport
+                });
+            }
+            Request::SetResolution { resolution } => self.world.resolution = resolution,
+            Request::SetCameras { cameras } => self.world.cameras = cameras,
+            Request::Exit { code } => self.exit_code = Some(code),
+            Request::ObjectSet { id, object } => {
+                Arc::make_mut(&mut self.world.scene).insert(id, scene_object(object));
+            }
+            Request::ObjectRemove { id } => {
+                Arc::make_mut(&mut self.world.scene).remove(id);
+            }
+            // Taken by the runtime, to be reported along with the trap
+            Request::Panic { .. } => (),
+            // Unpacked by the runtime
+            Request::Batch { .. } => (),
+            Request::StorageRead { key } => return Response::Storage(self.read_storage(key)),
+            Request::StorageWrite { key, bytes } => self.storage.write(self.instant, key, bytes),
+            Request::LoadAsset { path } => {
+                let bytes = self.load_asset(path).map(|bytes| bytes.to_vec());
+                return Response::Asset(bytes);
+            }
+            Request::Log {
+                level,
+                target,
+                message,
+                fields,
+            } => {
+                if !self.resimulating {
+                    log(self.game, self.instant, level, &target, message, fields);
+                }
+            }
+            Request::PlaySound {
+                path,
+                volume,
+                pan,
+                looping,
+            } => {
+                self.sounds.push(Sound {
+                    path,
+                    volume,
+                    pan,
+                    looping,
+                });
+            }
         }
 
         Response::Empty
     }
 }
+
+/// Convert a retained object for the scene
+pub(crate) fn scene_object(object: SceneObject) -> vg_scene::Object {
+    fn space<T>(space: Space, value: T) -> vg_scene::Space<T> {
+        match space {
+            Space::World => vg_scene::Space::World(value),
+            Space::View => vg_scene::Space::View(value),
+        }
+    }
+
+    let point = |(x, y): (f32, f32)| Vec2::new(x, y);
+    let color = |(r, g, b, a): (f32, f32, f32, f32)| Vec4::new(r, g, b, a);
+
+    let t = object.transform;
+    let transform = Mat4::from_cols(
+        point(t.x_axis).extend(0.0).extend(0.0),
+        point(t.y_axis).extend(0.0).extend(0.0),
+        Vec4::Z,
+        point(t.translation).extend(0.0).extend(1.0),
+    );
+
+    let shape = match object.shape {
+        Shape::Rect { min, max } => vg_scene::Shape::Rect {
+            min: point(min),
+            max: point(max),
+        },
+        Shape::Ellipse { center, radii } => vg_scene::Shape::Ellipse {
+            center: point(center),
+            radii: point(radii),
+        },
+        Shape::Polygon { points } => {
+            vg_scene::Shape::Polygon(points.into_iter().map(point).collect())
+        }
+        Shape::Line { points } => vg_scene::Shape::Line(points.into_iter().map(point).collect()),
+    };
+
+    vg_scene::Object {
+        transform: space(object.space, transform),
+        shape,
+        stroke: object.outline.map(|outline| vg_scene::Stroke {
+            thickness: space(outline.space, outline.width),
+            paint: match outline.paint {
+                OutlinePaint::Paint(paint) => vg_scene::StrokePaint::Paint(scene_paint(paint)),
+                OutlinePaint::Perimeter(colors) => {
+                    vg_scene::StrokePaint::Perimeter(colors.into_iter().map(color).collect())
+                }
+            },
+        }),
+        fill: object.fill.map(|fill| vg_scene::Fill {
+            paint: scene_paint(fill),
+        }),
+    }
+}
+
+/// Convert a paint for the scene
+pub(crate) fn scene_paint(paint: Paint) -> vg_scene::Paint {
+    let point = |(x, y): (f32, f32)| Vec2::new(x, y);
+    let color = |(r, g, b, a): (f32, f32, f32, f32)| Vec4::new(r, g, b, a);
+    let stops = |stops: Vec<vg_interface::ColorStop>| {
+        stops
+            .into_iter()
+            .map(|stop| (stop.offset, color(stop.color)))
+            .collect()
+    };
+    let extend = |extend| match extend {
+        Extend::Pad => vg_scene::Extend::Pad,
+        Extend::Repeat => vg_scene::Extend::Repeat,
+        Extend::Reflect => vg_scene::Extend::Reflect,
+    };
+
+    match paint {
+        Paint::Solid(c) => vg_scene::Paint::Solid(color(c)),
+        Paint::Linear {
+            start,
+            end,
+            stops: s,
+            extend: e,
+        } => vg_scene::Paint::Linear {
+            start: point(start),
+            end: point(end),
+            stops: stops(s),
+            extend: extend(e),
+        },
+        Paint::Radial {
+            center,
+            radius,
+            stops: s,
+            extend: e,
+        } => vg_scene::Paint::Radial {
+            center: point(center),
+            radius,
+            stops: stops(s),
+            extend: extend(e),
+        },
+        Paint::Sweep {
+            center,
+            start_angle,
+            end_angle,
+            stops: s,
+            extend: e,
+        } => vg_scene::Paint::Sweep {
+            center: point(center),
+            start_angle,
+            end_angle,
+            stops: stops(s),
+            extend: extend(e),
+        },
+        Paint::Image {
+            path,
+            transform: t,
+            extend: e,
+        } => vg_scene::Paint::Image {
+            path,
+            transform: glam::Affine2::from_cols(
+                point(t.x_axis),
+                point(t.y_axis),
+                point(t.translation),
+            ),
+            extend: extend(e),
+        },
+    }
+}
+
+/// Re-emit a game log message as a tracing event
+fn log(
+    game: &str,
+    instant: RuntimeInstant,
+    level: LogLevel,
+    target: &str,
+    message: String,
+    fields: Vec<(String, String)>,
+) {
+    // Tracing fields are static, so append the game's fields to the message
+    let mut message = message;
+    for (key, value) in fields {
+        message += &format!(" {key}={value}");
+    }
+
+    macro_rules! emit {
+        ($macro:ident) => {
+            tracing::$macro!(target: "game", game, %instant, module = target, "{message}")
+        };
+    }
+
+    match level {
+        LogLevel::Trace => emit!(trace),
+        LogLevel::Debug => emit!(debug),
+        LogLevel::Info => emit!(info),
+        LogLevel::Warn => emit!(warn),
+        LogLevel::Error => emit!(error),
+    }
+}
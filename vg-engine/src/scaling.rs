@@ -0,0 +1,69 @@
+//! Fitting the virtual resolution of a game onto the surface
+
+use vg_interface::{Point, Resolution, ScaleMode};
+
+use crate::prelude::*;
+
+/// Where the virtual canvas ends up on the surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Surface pixels per virtual unit
+    pub scale: Vec2,
+    /// Surface position of the virtual origin
+    pub offset: Vec2,
+    /// Visible area, in virtual units
+    pub visible: Vec2,
+    /// Is drawing outside the virtual canvas hidden behind bars
+    pub clip: bool,
+}
+
+impl Placement {
+    /// Fit a resolution onto a surface of some pixel size. Without a
+    /// resolution virtual units are surface pixels
+    pub fn new(surface: Vec2, resolution: Option<&Resolution>) -> Placement {
+        let Some(resolution) = resolution else {
+            return Placement {
+                scale: Vec2::ONE,
+                offset: Vec2::ZERO,
+                visible: surface,
+                clip: false,
+            };
+        };
+
+        // Zero sized surfaces still need to map somewhere, even if unseen
+        let size = Vec2::from(resolution.size).max(Vec2::ONE);
+        let stretch = (surface / size).max(Vec2::splat(f32::MIN_POSITIVE));
+        let fit = stretch.min_element();
+
+        let centered = |scale: f32| Placement {
+            scale: Vec2::splat(scale),
+            offset: ((surface - size * scale) / 2.0).round(),
+            visible: size,
+            clip: true,
+        };
+
+        match resolution.mode {
+            ScaleMode::Stretch => Placement {
+                scale: stretch,
+                offset: Vec2::ZERO,
+                visible: size,
+                clip: false,
+            },
+            ScaleMode::Letterbox => centered(fit),
+            // Shrink below 1x rather than show nothing on tiny windows
+            ScaleMode::Integer if fit < 1.0 => centered(fit),
+            ScaleMode::Integer => centered(fit.floor()),
+            ScaleMode::Expand => Placement {
+                scale: Vec2::splat(fit),
+                offset: Vec2::ZERO,
+                visible: surface / fit,
+                clip: false,
+            },
+        }
+    }
+
+    /// Map surface pixels to virtual units
+    pub fn to_virtual(self, (x, y): Point) -> Point {
+        ((Vec2::new(x, y) - self.offset) / self.scale).into()
+    }
+}
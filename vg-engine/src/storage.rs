@@ -0,0 +1,99 @@
+//! Persistent key-value storage for games
+//!
+//! Writes are held back while the tick that made them may still be rolled
+//! back, and only reach disk once confirmed. Reads see the pending writes of
+//! the current timeline first
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::{prelude::*, EngineConfig, RuntimeInstant};
+
+pub struct Storage {
+    /// Where confirmed values are written. Kept in memory only if None
+    dir: Option<PathBuf>,
+    /// Confirmed values, as a cache in front of the disk
+    committed: HashMap<String, Vec<u8>>,
+    /// Writes by ticks that may still be rolled back, in order
+    pending: Vec<(RuntimeInstant, String, Vec<u8>)>,
+}
+
+impl Storage {
+    pub fn new(dir: Option<PathBuf>) -> Storage {
+        Storage {
+            dir,
+            committed: HashMap::new(),
+            pending: vec![],
+        }
+    }
+
+    /// Sandboxed directory of a game, named after its id or binary
+    pub fn game_dir(config: &EngineConfig) -> Option<PathBuf> {
+        let name = encode(&config.game_name());
+        Some(dirs::data_dir()?.join("vg").join(name))
+    }
+
+    pub fn read(&mut self, key: &str) -> Option<Vec<u8>> {
+        if let Some((_, _, bytes)) = self.pending.iter().rev().find(|(_, k, _)| k == key) {
+            return Some(bytes.clone());
+        }
+        if let Some(bytes) = self.committed.get(key) {
+            return Some(bytes.clone());
+        }
+
+        let bytes = fs::read(self.dir.as_ref()?.join(encode(key))).ok()?;
+        self.committed.insert(key.to_string(), bytes.clone());
+        Some(bytes)
+    }
+
+    /// Write a value on behalf of a tick
+    pub fn write(&mut self, instant: RuntimeInstant, key: String, bytes: Vec<u8>) {
+        self.pending.push((instant, key, bytes));
+    }
+
+    /// Forget writes made by `instant` and the ticks after it
+    pub fn rewind(&mut self, instant: RuntimeInstant) {
+        self.pending.retain(|(at, _, _)| *at < instant);
+    }
+
+    /// Commit writes made before `instant`, which can't be rolled back anymore
+    pub fn confirm_before(&mut self, instant: RuntimeInstant) {
+        let split = self.pending.partition_point(|(at, _, _)| *at < instant);
+        let confirmed: Vec<_> = self.pending.drain(..split).collect();
+
+        for (_, key, bytes) in confirmed {
+            if let Some(dir) = &self.dir {
+                let result = fs::create_dir_all(dir)
+                    .and_then(|()| fs::write(dir.join(encode(&key)), &bytes));
+                if let Err(e) = result {
+                    error!(key, "Failed to write storage: {e}");
+                }
+            }
+            self.committed.insert(key, bytes);
+        }
+    }
+}
+
+impl Drop for Storage {
+    /// The timeline is final once the engine goes away
+    fn drop(&mut self) {
+        if let Some((last, _, _)) = self.pending.last() {
+            self.confirm_before(last.next_frame());
+        }
+    }
+}
+
+/// Turn a key into a file name that can't escape the storage directory
+fn encode(key: &str) -> String {
+    let mut name = String::new();
+    for byte in key.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+            _ => name.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    // Never produced otherwise
+    if name.is_empty() {
+        name.push('%');
+    }
+    name
+}
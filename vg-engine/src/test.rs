@@ -0,0 +1,383 @@
+use vg_asset::Assets;
+use vg_interface::{
+    Camera, ColorStop, Extend, Outline, OutlinePaint, PadAxis, PadButton, Paint, Resolution,
+    ScaleMode, SceneObject, Shape, Space, Transform,
+};
+
+use crate::{
+    audio::{Mixer, Sound},
+    head::perimeter_stops,
+    input::{InputState, PadEvent},
+    prelude::*,
+    runtime::{scene_object, WorldState},
+    scaling::Placement,
+    storage::Storage,
+    Engine, EngineConfig, RuntimeInstant,
+};
+
+#[test]
+fn gamepad_snapshots() {
+    let mut input = InputState::default();
+    input.pad_event(7, PadEvent::Connected);
+    input.pad_event(7, PadEvent::Pressed(PadButton::South));
+    input.pad_event(7, PadEvent::Axis(PadAxis::LeftStickX, 0.5));
+
+    let tick = input.snapshot();
+    let pad = &tick.gamepads[0];
+    assert!(pad.connected && pad.connection_changed);
+    assert!(pad.pressed(PadButton::South) && pad.down(PadButton::South));
+    assert_eq!(pad.axis(PadAxis::LeftStickX), 0.5);
+
+    // Only held state carries over to the next tick
+    let tick = input.snapshot();
+    let pad = &tick.gamepads[0];
+    assert!(!pad.connection_changed && !pad.pressed(PadButton::South));
+    assert!(pad.down(PadButton::South));
+
+    input.pad_event(7, PadEvent::Disconnected);
+    let tick = input.snapshot();
+    let pad = &tick.gamepads[0];
+    assert!(!pad.connected && pad.released(PadButton::South));
+    assert_eq!(pad.axis(PadAxis::LeftStickX), 0.0);
+}
+
+#[test]
+fn gamepad_index_is_stable() {
+    let mut input = InputState::default();
+    input.pad_event(7, PadEvent::Connected);
+    input.pad_event(3, PadEvent::Connected);
+    input.pad_event(7, PadEvent::Disconnected);
+    input.pad_event(7, PadEvent::Connected);
+
+    let tick = input.snapshot();
+    let indices: Vec<_> = tick.gamepads.iter().map(|pad| pad.index).collect();
+    assert_eq!(indices, [0, 1]);
+    assert!(tick.gamepads.iter().all(|pad| pad.connected));
+}
+
+/// Mono 16-bit PCM file
+fn wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+
+    let mut bytes: Vec<u8> = vec![];
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_len).to_le_bytes());
+    bytes.extend(b"WAVEfmt ");
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes()); // PCM
+    bytes.extend(1u16.to_le_bytes()); // Channels
+    bytes.extend(sample_rate.to_le_bytes());
+    bytes.extend((sample_rate * 2).to_le_bytes());
+    bytes.extend(2u16.to_le_bytes()); // Block size
+    bytes.extend(16u16.to_le_bytes()); // Bits per sample
+    bytes.extend(b"data");
+    bytes.extend(data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend(sample.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn mixer_follows_rollback() {
+    let assets = Assets::new();
+    let mut mixer = Mixer::new(Arc::clone(&assets), 60);
+    let beep = Sound {
+        path: "beep.wav".into(),
+        volume: 1.0,
+        pan: 0.0,
+        looping: true,
+    };
+    let start = RuntimeInstant::EPOCH.next_frame();
+
+    mixer.tick(RuntimeInstant::EPOCH, vec![]);
+    mixer.tick(start, vec![beep.clone()]);
+    assets.update("beep.wav", wav(&[16384; 100], mixer.sample_rate()));
+
+    let mut out = [0.0; 64];
+    mixer.mix(&mut out);
+    assert!(out.iter().all(|&s| (s - 0.5).abs() < 1e-3));
+
+    // Simulating the tick again doesn't start a second copy
+    mixer.rewind(RuntimeInstant::EPOCH);
+    mixer.tick(RuntimeInstant::EPOCH, vec![]);
+    mixer.tick(start, vec![beep]);
+    mixer.mix(&mut out);
+    assert!(out.iter().all(|&s| (s - 0.5).abs() < 1e-3));
+
+    // The sound fades out when the new timeline doesn't play it
+    mixer.rewind(RuntimeInstant::EPOCH);
+    mixer.tick(RuntimeInstant::EPOCH, vec![]);
+    mixer.tick(start, vec![]);
+    let mut out = vec![0.0; mixer.sample_rate() as usize];
+    mixer.mix(&mut out);
+    assert!(out[0] > 0.0 && out[2] < out[0]);
+    assert_eq!(*out.last().unwrap(), 0.0);
+
+    mixer.mix(&mut out);
+    assert!(out.iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn mixer_ends_unmixed_sounds() {
+    let assets = Assets::new();
+    let mut mixer = Mixer::new(Arc::clone(&assets), 60);
+    let click = Sound {
+        path: "click.wav".into(),
+        volume: 1.0,
+        pan: 0.0,
+        looping: false,
+    };
+    assets.update("click.wav", wav(&[16384; 100], mixer.sample_rate()));
+
+    // Nothing mixes while the game keeps going
+    let mut instant = RuntimeInstant::EPOCH;
+    mixer.tick(instant, vec![click.clone()]);
+    for _ in 0..60 {
+        instant = instant.next_frame();
+        mixer.tick(instant, vec![]);
+    }
+
+    // It is over by now, and simulating its tick again doesn't replay it
+    mixer.rewind(RuntimeInstant::EPOCH);
+    mixer.tick(RuntimeInstant::EPOCH, vec![click]);
+    let mut out = [0.0; 64];
+    mixer.mix(&mut out);
+    assert!(out.iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn resolution_placement() {
+    let resolution = |mode| Resolution {
+        size: (320.0, 180.0),
+        mode,
+        clear: (0.0, 0.0, 0.0, 1.0),
+    };
+    let surface = Vec2::new(1000.0, 600.0);
+
+    let letterbox = Placement::new(surface, Some(&resolution(ScaleMode::Letterbox)));
+    assert_eq!(letterbox.scale, Vec2::splat(3.125));
+    assert_eq!(letterbox.offset, Vec2::new(0.0, 19.0));
+    assert_eq!(letterbox.to_virtual((500.0, 19.0)), (160.0, 0.0));
+
+    let integer = Placement::new(surface, Some(&resolution(ScaleMode::Integer)));
+    assert_eq!(integer.scale, Vec2::splat(3.0));
+    assert_eq!(integer.offset, Vec2::new(20.0, 30.0));
+
+    let expand = Placement::new(surface, Some(&resolution(ScaleMode::Expand)));
+    assert_eq!(expand.visible, Vec2::new(320.0, 192.0));
+
+    let none = Placement::new(surface, None);
+    assert_eq!(none.to_virtual((12.0, 34.0)), (12.0, 34.0));
+}
+
+#[test]
+fn storage_waits_for_confirmation() {
+    let dir = std::env::temp_dir().join(format!("vg-storage-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let tick = |frame| RuntimeInstant::EPOCH.relative_frame(frame);
+
+    let mut storage = Storage::new(Some(dir.clone()));
+    storage.write(tick(1), "score".into(), vec![1]);
+    storage.write(tick(3), "score".into(), vec![3]);
+    assert_eq!(storage.read("score"), Some(vec![3]));
+
+    // Rolled back writes are never seen again
+    storage.rewind(tick(2));
+    assert_eq!(storage.read("score"), Some(vec![1]));
+    assert!(!dir.exists());
+
+    storage.confirm_before(tick(2));
+    assert_eq!(std::fs::read(dir.join("score")).unwrap(), [1]);
+
+    // Unsafe keys stay inside the directory
+    storage.write(tick(4), "../escape".into(), vec![4]);
+    drop(storage);
+    assert!(dir.join("%2E%2E%2Fescape").exists());
+
+    let mut storage = Storage::new(Some(dir.clone()));
+    assert_eq!(storage.read("../escape"), Some(vec![4]));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scene_carries_over_ticks() {
+    let object = scene_object(SceneObject {
+        space: Space::View,
+        transform: Transform {
+            translation: (3.0, 4.0),
+            ..Transform::IDENTITY
+        },
+        shape: Shape::Line {
+            points: vec![(0.0, 0.0), (1.0, 1.0)],
+        },
+        fill: None,
+        outline: Some(Outline {
+            width: 2.0,
+            space: Space::World,
+            paint: OutlinePaint::Perimeter(vec![(1.0, 0.0, 0.0, 1.0), (0.0, 0.0, 1.0, 1.0)]),
+        }),
+    });
+    let translation = Mat4::from_translation(Vec3::new(3.0, 4.0, 0.0));
+    assert_eq!(object.transform, vg_scene::Space::View(translation));
+    assert_eq!(
+        object.stroke.as_ref().unwrap().paint,
+        vg_scene::StrokePaint::Perimeter(vec![
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec4::new(0.0, 0.0, 1.0, 1.0)
+        ])
+    );
+
+    let mut world = WorldState::default();
+    Arc::make_mut(&mut world.scene).insert(0, object);
+
+    // Unchanged scenes are shared rather than copied every tick
+    let next = world.next();
+    assert!(Arc::ptr_eq(&world.scene, &next.scene));
+    assert_eq!(next.scene.len(), 1);
+}
+
+#[test]
+fn scene_fill_paints() {
+    let fill = |paint| {
+        scene_object(SceneObject {
+            space: Space::World,
+            transform: Transform::IDENTITY,
+            shape: Shape::Rect {
+                min: (0.0, 0.0),
+                max: (4.0, 4.0),
+            },
+            fill: Some(paint),
+            outline: None,
+        })
+        .fill
+        .unwrap()
+        .paint
+    };
+
+    let linear = fill(Paint::Linear {
+        start: (0.0, 0.0),
+        end: (4.0, 0.0),
+        stops: vec![
+            ColorStop {
+                offset: 0.0,
+                color: (1.0, 0.0, 0.0, 1.0),
+            },
+            ColorStop {
+                offset: 1.0,
+                color: (0.0, 0.0, 1.0, 1.0),
+            },
+        ],
+        extend: Extend::Reflect,
+    });
+    assert_eq!(
+        linear,
+        vg_scene::Paint::Linear {
+            start: Vec2::ZERO,
+            end: Vec2::new(4.0, 0.0),
+            stops: vec![
+                (0.0, Vec4::new(1.0, 0.0, 0.0, 1.0)),
+                (1.0, Vec4::new(0.0, 0.0, 1.0, 1.0))
+            ],
+            extend: vg_scene::Extend::Reflect,
+        }
+    );
+
+    // Image patterns keep their placement relative to the shape
+    let image = fill(Paint::Image {
+        path: "tile.png".into(),
+        transform: Transform {
+            translation: (1.0, 2.0),
+            ..Transform::IDENTITY
+        },
+        extend: Extend::Repeat,
+    });
+    assert_eq!(
+        image,
+        vg_scene::Paint::Image {
+            path: "tile.png".into(),
+            transform: glam::Affine2::from_translation(Vec2::new(1.0, 2.0)),
+            extend: vg_scene::Extend::Repeat,
+        }
+    );
+}
+
+#[test]
+fn camera_transform() {
+    let apply = |t: Transform, (x, y): (f32, f32)| {
+        Vec2::from(t.x_axis) * x + Vec2::from(t.y_axis) * y + Vec2::from(t.translation)
+    };
+
+    // Looking at the middle of the canvas changes nothing
+    let camera = Camera {
+        position: (160.0, 90.0),
+        zoom: 1.0,
+        rotation: 0.0,
+        viewport: ((0.0, 0.0), (1.0, 1.0)),
+    };
+    assert_eq!(camera.transform((320.0, 180.0)), Transform::IDENTITY);
+
+    // Right half of a split screen, zoomed in
+    let camera = Camera {
+        position: (10.0, 0.0),
+        zoom: 2.0,
+        viewport: ((0.5, 0.0), (1.0, 1.0)),
+        ..camera
+    };
+    let transform = camera.transform((200.0, 100.0));
+    assert_eq!(
+        camera.viewport_rect((200.0, 100.0)),
+        ((100.0, 0.0), (200.0, 100.0))
+    );
+    assert_eq!(apply(transform, (10.0, 0.0)), Vec2::new(150.0, 50.0));
+    assert_eq!(apply(transform, (11.0, 0.0)), Vec2::new(152.0, 50.0));
+}
+
+#[test]
+fn headless_load_failure() {
+    let engine = Engine::with_config(EngineConfig {
+        headless: true,
+        path: "broken.wasm".into(),
+        ..EngineConfig::new()
+    });
+
+    // Fails validation instead of waiting for a game that never loads
+    engine.assets().update("broken.wasm", b"(module)".to_vec());
+    let error = engine.run_headless().unwrap_err().to_string();
+    assert!(error.contains("__vg_abi_version"), "{error}");
+}
+
+#[test]
+fn perimeter_colors() {
+    let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+    let green = Vec4::new(0.0, 1.0, 0.0, 1.0);
+    let blue = Vec4::new(0.0, 0.0, 1.0, 1.0);
+    let white = Vec4::ONE;
+    let colors = [red, green, blue, white];
+
+    // Passing a color partway along a piece adds a stop there
+    assert_eq!(
+        perimeter_stops(&colors, 0.125, 0.375, true),
+        vec![
+            (0.0, red.lerp(green, 0.5)),
+            (0.5, green),
+            (1.0, green.lerp(blue, 0.5)),
+        ]
+    );
+
+    // Closed outlines blend back into the first color
+    assert_eq!(
+        perimeter_stops(&colors, 0.875, 1.0, true),
+        vec![(0.0, white.lerp(red, 0.5)), (1.0, red)]
+    );
+
+    // Open lines end on the last color
+    assert_eq!(
+        perimeter_stops(&colors[..3], 0.0, 1.0, false),
+        vec![(0.0, red), (0.5, green), (1.0, blue)]
+    );
+    assert_eq!(
+        perimeter_stops(&[white], 0.25, 0.5, false),
+        vec![(0.0, white), (1.0, white)]
+    );
+}
@@ -1,22 +1,336 @@
 //! Types for communicating between vg-runtime and vg-rust
-pub use nanoserde::{DeBin, SerBin};
+pub use nanoserde::{DeBin, DeJson, SerBin};
+
+/// Version of the protocol between games and the runtime. Bumped whenever
+/// requests, responses or the functions games export change incompatibly
+pub const ABI_VERSION: u32 = 1;
 
 #[derive(SerBin, DeBin, Debug)]
 pub enum Request {
-    Draw(Draw),
+    /// Draw with the guest's current transform. World space draws are seen
+    /// through the cameras, view space draws are fixed on the canvas
+    Draw {
+        transform: Transform,
+        space: Space,
+        draw: Draw,
+    },
+    /// Draw a development gizmo, only shown while the engine's debug overlay
+    /// is on. Same as `Draw` otherwise
+    DebugDraw {
+        transform: Transform,
+        space: Space,
+        draw: Draw,
+    },
+    /// Get the input snapshot for this tick
+    Input,
+    /// Get the simulated time of this tick
+    Time,
+    /// Get the random seed of this session
+    Seed,
+    /// Start playing a sound asset. `pan` goes from -1 (left) to 1 (right)
+    PlaySound {
+        path: String,
+        volume: f32,
+        pan: f32,
+        looping: bool,
+    },
+    /// Get the window state for this tick
+    Viewport,
+    /// Draw in virtual units instead of surface pixels, from this tick on.
+    /// None goes back to surface pixels
+    SetResolution { resolution: Option<Resolution> },
+    /// Show world space through these cameras, from this tick on. Empty draws
+    /// world space directly in canvas units
+    SetCameras { cameras: Vec<Camera> },
+    /// Read a value saved by the game, in this or an earlier session
+    StorageRead { key: String },
+    /// Save a value, kept between sessions
+    StorageWrite { key: String, bytes: Vec<u8> },
+    /// Get the contents of an asset file, starting to load it if needed
+    LoadAsset { path: String },
+    /// Emit a log message from the game
+    Log {
+        level: LogLevel,
+        /// Module path of the log statement
+        target: String,
+        message: String,
+        fields: Vec<(String, String)>,
+    },
+    /// The game is panicking. Sent by the panic hook, right before the
+    /// instance traps
+    Panic { message: String },
+    /// The game is done, and should stop with an exit code
+    Exit { code: i32 },
+    /// Add a retained object, or replace the one with the same id
+    ObjectSet { id: u64, object: SceneObject },
+    /// Remove a retained object
+    ObjectRemove { id: u64 },
+    /// Several requests that don't need an answer, in order. Saves a round
+    /// trip for each
+    Batch { requests: Vec<Request> },
 }
 
+/// RGBA color with components in 0 to 1 range
+pub type Color = (f32, f32, f32, f32);
+/// Position or size on the canvas
+pub type Point = (f32, f32);
+
 #[derive(SerBin, DeBin, Debug, Clone)]
 pub enum Draw {
+    /// Open polyline through all the points
     Line {
-        color: (f32, f32, f32, f32),
-        points: Vec<(f32, f32)>,
+        stroke: Stroke,
+        points: Vec<Point>,
+    },
+    Rect {
+        min: Point,
+        max: Point,
+        style: Style,
+    },
+    /// Circles are ellipses with equal radii
+    Ellipse {
+        center: Point,
+        radii: Point,
+        style: Style,
+    },
+    /// Closed shape through all the points
+    Polygon {
+        points: Vec<Point>,
+        style: Style,
+    },
+    Path {
+        segments: Vec<PathSegment>,
+        style: Style,
+    },
+    Text {
+        text: String,
+        /// Asset path of a TrueType or OpenType font. None for the engine default
+        font: Option<String>,
+        size: f32,
+        /// Positions are relative to `position`. Image paints are placed from
+        /// there too, without their transform
+        paint: Paint,
+        /// Top edge of the text, horizontally anchored by `align`
+        position: Point,
+        align: Align,
+    },
+    Image {
+        /// Asset path of the image
+        path: String,
+        /// Region of the image in pixels as (min, max). None for the whole image
+        src_rect: Option<(Point, Point)>,
+        /// Maps image pixels, relative to `src_rect`, onto the canvas
+        dst_transform: Transform,
+        /// Multiplied with the image colors
+        tint: Color,
+    },
+}
+
+/// Shape kept by the engine and drawn every frame until removed
+#[derive(SerBin, DeBin, Debug, Clone, PartialEq)]
+pub struct SceneObject {
+    /// World objects are seen through the cameras, view objects are fixed on
+    /// the canvas over the world
+    pub space: Space,
+    pub transform: Transform,
+    pub shape: Shape,
+    pub fill: Option<Paint>,
+    pub outline: Option<Outline>,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rect { min: Point, max: Point },
+    Ellipse { center: Point, radii: Point },
+    /// Closed shape through all the points
+    Polygon { points: Vec<Point> },
+    /// Open polyline through all the points. Never filled
+    Line { points: Vec<Point> },
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, PartialEq)]
+pub struct Outline {
+    pub width: f32,
+    /// World space widths scale with transforms, view space widths are in pixels
+    pub space: Space,
+    pub paint: OutlinePaint,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, PartialEq)]
+pub enum OutlinePaint {
+    Paint(Paint),
+    /// Spread evenly along the outline, blending in between
+    Perimeter(Vec<Color>),
+}
+
+/// 2D affine transformation, with the columns of a 3x3 matrix minus the
+/// last row
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub x_axis: Point,
+    pub y_axis: Point,
+    pub translation: Point,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        x_axis: (1.0, 0.0),
+        y_axis: (0.0, 1.0),
+        translation: (0.0, 0.0),
+    };
+}
+
+/// Horizontal alignment of text lines relative to their position
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// How a closed shape is painted. Nothing is drawn if both are None
+#[derive(SerBin, DeBin, Debug, Clone, Default)]
+pub struct Style {
+    pub fill: Option<Paint>,
+    pub stroke: Option<Stroke>,
+}
+
+/// Color source of fills and strokes. Positions are in the same units as the
+/// shape, and move along with it
+#[derive(SerBin, DeBin, Debug, Clone, PartialEq)]
+pub enum Paint {
+    Solid(Color),
+    /// Colors along the line from `start` to `end`
+    Linear {
+        start: Point,
+        end: Point,
+        stops: Vec<ColorStop>,
+        extend: Extend,
+    },
+    /// Colors from `center` out to `radius`
+    Radial {
+        center: Point,
+        radius: f32,
+        stops: Vec<ColorStop>,
+        extend: Extend,
+    },
+    /// Colors around `center`, from `start_angle` to `end_angle` in radians
+    Sweep {
+        center: Point,
+        start_angle: f32,
+        end_angle: f32,
+        stops: Vec<ColorStop>,
+        extend: Extend,
+    },
+    /// Image asset repeated over the shape. Nothing is drawn until it has
+    /// loaded
+    Image {
+        path: String,
+        /// Maps image pixels onto the shape
+        transform: Transform,
+        extend: Extend,
     },
 }
 
+/// Color at some offset from 0 to 1 along a gradient
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// What a gradient or image does past its ends
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extend {
+    /// Continue the color at the edge
+    Pad,
+    Repeat,
+    /// Repeat, mirroring every other time
+    Reflect,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone)]
+pub struct Stroke {
+    pub paint: Paint,
+    pub width: f32,
+    /// World space widths scale with transforms, view space widths are in pixels
+    pub space: Space,
+    pub cap: Cap,
+    pub join: Join,
+    /// Ratio of miter length to width before a miter join turns into a bevel
+    pub miter_limit: f32,
+    /// Alternating lengths of dashes and gaps. Empty for a solid line
+    pub dashes: Vec<f32>,
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    /// One unit wide stroke
+    pub fn new(paint: Paint) -> Stroke {
+        Stroke {
+            paint,
+            width: 1.0,
+            space: Space::World,
+            cap: Cap::Butt,
+            join: Join::Miter,
+            miter_limit: 4.0,
+            dashes: vec![],
+            dash_offset: 0.0,
+        }
+    }
+}
+
+/// Coordinate space a value is measured in
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Space {
+    World,
+    View,
+}
+
+/// Shape at the ends of open lines
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cap {
+    Butt,
+    Round,
+    Square,
+}
+
+/// Shape of the corners where line segments meet
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Join {
+    Bevel,
+    Miter,
+    Round,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy)]
+pub enum PathSegment {
+    /// Begin a new subpath
+    MoveTo { to: Point },
+    LineTo { to: Point },
+    /// Quadratic Bézier curve
+    QuadTo { control: Point, to: Point },
+    /// Cubic Bézier curve
+    CubicTo {
+        control1: Point,
+        control2: Point,
+        to: Point,
+    },
+    /// Connect back to the start of the current subpath
+    Close,
+}
+
 #[derive(SerBin, DeBin, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Response {
     Empty,
+    Input(Input),
+    Time(Time),
+    Seed(u64),
+    Viewport(Viewport),
+    Storage(Option<Vec<u8>>),
+    /// Asset contents, None while still loading
+    Asset(Option<Vec<u8>>),
 }
 
 impl Response {
@@ -28,6 +342,379 @@ impl Response {
             _ => panic!("expected empty response"),
         }
     }
+
+    pub fn unwrap_input(self) -> Input {
+        match self {
+            Response::Input(input) => input,
+            _ => panic!("expected input response"),
+        }
+    }
+
+    pub fn unwrap_time(self) -> Time {
+        match self {
+            Response::Time(time) => time,
+            _ => panic!("expected time response"),
+        }
+    }
+
+    pub fn unwrap_seed(self) -> u64 {
+        match self {
+            Response::Seed(seed) => seed,
+            _ => panic!("expected seed response"),
+        }
+    }
+
+    pub fn unwrap_viewport(self) -> Viewport {
+        match self {
+            Response::Viewport(viewport) => viewport,
+            _ => panic!("expected viewport response"),
+        }
+    }
+
+    pub fn unwrap_storage(self) -> Option<Vec<u8>> {
+        match self {
+            Response::Storage(bytes) => bytes,
+            _ => panic!("expected storage response"),
+        }
+    }
+
+    pub fn unwrap_asset(self) -> Option<Vec<u8>> {
+        match self {
+            Response::Asset(bytes) => bytes,
+            _ => panic!("expected asset response"),
+        }
+    }
+}
+
+/// Simulated time of a tick. Only depends on the tick index, never on the wall
+/// clock
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Time {
+    /// Index of the tick, starting from 0
+    pub tick: u64,
+    /// Fixed length of a tick in seconds
+    pub delta: f32,
+    /// Seconds simulated before this tick
+    pub elapsed: f64,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Virtual canvas size, and how it is fitted onto the surface
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub size: Point,
+    pub mode: ScaleMode,
+    /// Background color, also filling any bars around the canvas
+    pub clear: Color,
+}
+
+/// 2D camera showing world space on part of the canvas
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// World position shown at the center of the viewport
+    pub position: Point,
+    /// Canvas units per world unit
+    pub zoom: f32,
+    /// Rotation of the camera in radians. The world turns the other way
+    pub rotation: f32,
+    /// Area of the canvas drawn to as (min, max), in fractions of its size
+    pub viewport: (Point, Point),
+}
+
+impl Camera {
+    /// Viewport in canvas units, as (min, max)
+    pub fn viewport_rect(&self, canvas_size: Point) -> (Point, Point) {
+        let ((x0, y0), (x1, y1)) = self.viewport;
+        let (w, h) = canvas_size;
+        ((x0 * w, y0 * h), (x1 * w, y1 * h))
+    }
+
+    /// Maps world space onto the canvas
+    pub fn transform(&self, canvas_size: Point) -> Transform {
+        let ((x0, y0), (x1, y1)) = self.viewport_rect(canvas_size);
+        let center = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let x_axis = (cos * self.zoom, sin * self.zoom);
+        let y_axis = (-sin * self.zoom, cos * self.zoom);
+
+        // Camera position ends up at the center
+        let (px, py) = self.position;
+        Transform {
+            x_axis,
+            y_axis,
+            translation: (
+                center.0 - x_axis.0 * px - y_axis.0 * py,
+                center.1 - x_axis.1 * px - y_axis.1 * py,
+            ),
+        }
+    }
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Fill the surface, distorting the aspect ratio
+    Stretch,
+    /// Scale as large as fits, with bars on the sides
+    Letterbox,
+    /// Like letterbox, but only by whole multiples for crisp pixel art
+    Integer,
+    /// Scale as large as fits, and show more of the world beyond the right
+    /// and bottom edges instead of bars
+    Expand,
+}
+
+/// Window state as seen by one tick. Zero sized when headless
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// Visible area in the units of draws and cursor positions. The virtual
+    /// resolution if one is set, possibly extended by `ScaleMode::Expand`
+    pub canvas_size: Point,
+    /// Surface size in pixels
+    pub physical_size: Point,
+    /// Surface size divided by `scale`
+    pub size: Point,
+    /// DPI scale factor of the display
+    pub scale: f32,
+    pub focused: bool,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            canvas_size: (0.0, 0.0),
+            physical_size: (0.0, 0.0),
+            size: (0.0, 0.0),
+            scale: 1.0,
+            focused: false,
+        }
+    }
+}
+
+/// Everything the player did, as seen by one tick. Pressed and released
+/// lists include everything since the previous tick, so short taps between
+/// ticks are not lost
+#[derive(SerBin, DeBin, Debug, Clone, Default, PartialEq)]
+pub struct Input {
+    pub keys_down: Vec<Key>,
+    pub keys_pressed: Vec<Key>,
+    pub keys_released: Vec<Key>,
+    /// Cursor position in virtual units when a virtual resolution is set,
+    /// otherwise in surface pixels. None if outside the window
+    pub cursor: Option<Point>,
+    pub buttons_down: Vec<MouseButton>,
+    pub buttons_pressed: Vec<MouseButton>,
+    pub buttons_released: Vec<MouseButton>,
+    /// Scrolled amount in lines
+    pub wheel: Point,
+    /// Every gamepad seen during this session, ordered by index
+    pub gamepads: Vec<Gamepad>,
+    /// Touches active during the tick, and ones lifted since the previous tick
+    pub touches: Vec<Touch>,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    /// Identifies the finger until it is lifted
+    pub id: u64,
+    pub phase: TouchPhase,
+    /// In the same units as `Input::cursor`
+    pub position: Point,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    /// Touched down since the previous tick
+    Started,
+    Moved,
+    /// Held without moving since the previous tick
+    Stationary,
+    /// Lifted since the previous tick. The last time this touch is seen
+    Ended,
+    /// Interrupted by the system. The last time this touch is seen
+    Cancelled,
+}
+
+impl TouchPhase {
+    /// Is the finger still on the screen
+    pub fn is_active(self) -> bool {
+        !matches!(self, TouchPhase::Ended | TouchPhase::Cancelled)
+    }
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Default, PartialEq)]
+pub struct Gamepad {
+    /// Stable index, kept by the pad if it disconnects and reconnects
+    pub index: u32,
+    pub connected: bool,
+    /// Connected or disconnected since the previous tick
+    pub connection_changed: bool,
+    pub buttons_down: Vec<PadButton>,
+    pub buttons_pressed: Vec<PadButton>,
+    pub buttons_released: Vec<PadButton>,
+    /// Axis positions from -1 to 1. Axes not listed are at rest
+    pub axes: Vec<(PadAxis, f32)>,
+}
+
+impl Gamepad {
+    /// Is the button held down
+    pub fn down(&self, button: PadButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    /// Was the button pressed since the previous tick
+    pub fn pressed(&self, button: PadButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Was the button released since the previous tick
+    pub fn released(&self, button: PadButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Position of an axis, 0 if at rest
+    pub fn axis(&self, axis: PadAxis) -> f32 {
+        self.axes
+            .iter()
+            .find(|(a, _)| *a == axis)
+            .map_or(0.0, |(_, v)| *v)
+    }
+}
+
+/// Gamepad buttons, by position on a typical controller
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Analog inputs of a gamepad. Stick Y axes are positive upwards, triggers go
+/// from 0 to 1
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// Physical keyboard key, named after the US layout
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    LeftShift,
+    RightShift,
+    LeftControl,
+    RightControl,
+    LeftAlt,
+    RightAlt,
+    Minus,
+    Equal,
+    Comma,
+    Period,
+    Slash,
+    Backslash,
+    Semicolon,
+    Quote,
+    Backquote,
+    LeftBracket,
+    RightBracket,
+}
+
+#[derive(SerBin, DeBin, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
 }
 
 macro_rules! def_enum {
@@ -60,7 +747,9 @@ macro_rules! def_enum {
 def_enum! {
     enum WaitReason {
         Startup = 0,
-        Present = 1
+        Present = 1,
+        Exit = 2,
+        Pending = 3
     }
 }
 
@@ -68,4 +757,20 @@ impl WaitReason {
     pub fn is_present(&self) -> bool {
         *self == WaitReason::Present
     }
+
+    /// The game is waiting for something outside of it, like an asset to
+    /// load. The tick ends without a new frame
+    pub fn is_pending(&self) -> bool {
+        *self == WaitReason::Pending
+    }
+
+    /// No more steps this tick
+    pub fn ends_tick(&self) -> bool {
+        self.is_present() || self.is_pending()
+    }
+
+    /// The game has exited, and must not be stepped again
+    pub fn is_exit(&self) -> bool {
+        *self == WaitReason::Exit
+    }
 }
@@ -0,0 +1,106 @@
+//! Cost of sending draws to the runtime one request at a time, compared to
+//! sending them all in one batch like vg-rust does
+//!
+//! Run with `cargo bench -p vg-runtime`
+#![feature(test)]
+
+extern crate test;
+
+use std::fmt::Write;
+
+use test::Bencher;
+use vg_interface::{Draw, Paint, Request, Response, SerBin, Space, Style, Transform, ABI_VERSION};
+use vg_runtime::{
+    executor::{Instance, WasmInstance},
+    Provider,
+};
+
+/// Draws sent every step
+const DRAWS: usize = 1000;
+
+/// Counts the draws it is given
+struct Sink {
+    draws: usize,
+}
+
+impl Provider for Sink {
+    fn provide(&mut self, request: Request) -> Response {
+        if let Request::Draw { .. } = request {
+            self.draws += 1;
+        }
+        Response::Empty
+    }
+}
+
+fn draw(i: usize) -> Request {
+    Request::Draw {
+        transform: Transform::IDENTITY,
+        space: Space::World,
+        draw: Draw::Rect {
+            min: (i as f32, 0.0),
+            max: (i as f32 + 1.0, 1.0),
+            style: Style {
+                fill: Some(Paint::Solid((1.0, 1.0, 1.0, 1.0))),
+                stroke: None,
+            },
+        },
+    }
+}
+
+/// Guest that sends premade requests on every step, reading back each
+/// response like `vg::ffi::dispatch`
+fn guest(requests: &[Vec<u8>]) -> WasmInstance {
+    let data = requests.concat();
+    let response = data.len();
+    let pages = (response + 1024) / 65536 + 1;
+
+    let bytes: String = data.iter().map(|byte| format!("\\{byte:02x}")).collect();
+    let mut calls = String::new();
+    let mut offset = 0;
+    for request in requests {
+        let len = request.len();
+        writeln!(
+            calls,
+            "(drop (call $request (i32.const {offset}) (i32.const {len})))"
+        )
+        .unwrap();
+        writeln!(calls, "(call $response (i32.const {response}))").unwrap();
+        offset += len;
+    }
+
+    let wat = format!(
+        r#"(module
+            (import "env" "__vg_request" (func $request (param i32 i32) (result i32)))
+            (import "env" "__vg_response" (func $response (param i32)))
+            (memory (export "memory") {pages})
+            (data (i32.const 0) "{bytes}")
+            (func (export "_start"))
+            (func (export "__vg_abi_version") (result i32) (i32.const {ABI_VERSION}))
+            (func (export "__vg_step") (result i32)
+                {calls}
+                (i32.const 1)))"#
+    );
+
+    WasmInstance::new(wat.as_bytes(), false).unwrap()
+}
+
+/// Step the guest, checking every draw arrives
+fn step(b: &mut Bencher, mut instance: WasmInstance) {
+    let mut sink = Sink { draws: 0 };
+    b.iter(|| instance.step(&mut sink).unwrap());
+    assert!(sink.draws >= DRAWS && sink.draws % DRAWS == 0);
+}
+
+#[bench]
+fn draws_one_by_one(b: &mut Bencher) {
+    let requests: Vec<_> = (0..DRAWS).map(|i| draw(i).serialize_bin()).collect();
+    step(b, guest(&requests));
+}
+
+#[bench]
+fn draws_batched(b: &mut Bencher) {
+    let batch = Request::Batch {
+        requests: (0..DRAWS).map(draw).collect(),
+    };
+    step(b, guest(&[batch.serialize_bin()]));
+}
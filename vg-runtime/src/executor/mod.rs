@@ -1,11 +1,11 @@
 pub mod wasmtime;
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display, path::Path, sync::Arc};
 
 use anyhow::Result;
 use get_size::GetSize;
 use serde::{de::Visitor, Deserialize, Serialize};
-use vg_asset::AssetKind;
+use vg_asset::{Asset, AssetKind, Assets, BinAsset};
 use vg_interface::WaitReason;
 
 use crate::Provider;
@@ -19,13 +19,87 @@ pub trait Instance: AssetKind {
     fn new(bytes: &[u8], debug: bool) -> Result<Self>;
 
     /// Step instance state by one. Note that this is different from a _tick_
-    fn step<T: Provider>(&mut self, provider: &mut T) -> WaitReason;
+    ///
+    /// If the game traps, the instance is left unusable until data from
+    /// before the error is set
+    fn step<T: Provider>(&mut self, provider: &mut T) -> Result<WaitReason, GuestError>;
     /// Serialize instance data
     fn get_data(&mut self) -> InstanceData;
     /// Deserialize in place. Data must come from identical Instance
     fn set_data(&mut self, data: &InstanceData);
 }
 
+/// Instance created from a module asset, or why it couldn't be. Loading is
+/// tried again once the module changes
+pub enum Loaded<I> {
+    Ready(I),
+    /// The module failed validation or instantiation
+    Failed(String),
+}
+
+impl<I: Instance> AssetKind for Loaded<I> {
+    /// Bytecode source
+    type Data = Asset<BinAsset>;
+
+    fn new(assets: &Arc<Assets>, path: &Path) -> Self::Data {
+        assets.get(path)
+    }
+
+    fn produce(data: &mut Self::Data) -> Option<Self> {
+        let bin = data.get()?;
+        Some(match I::new(&bin.bytes, true) {
+            Ok(instance) => Loaded::Ready(instance),
+            Err(error) => {
+                tracing::error!("Failed to load game: {error:#}");
+                Loaded::Failed(format!("{error:#}"))
+            }
+        })
+    }
+}
+
+/// The game failed during a step
+#[derive(Debug, Clone)]
+pub struct GuestError {
+    /// What stopped the instance, like an unreachable instruction
+    pub trap: String,
+    /// Message given by the panic hook, if the trap was caused by a panic
+    pub panic: Option<String>,
+    /// Wasm call stack at the time of the trap, innermost first
+    pub backtrace: Vec<GuestFrame>,
+}
+
+/// Function in a guest backtrace. Names come from debug info if available
+#[derive(Debug, Clone)]
+pub struct GuestFrame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+impl std::error::Error for GuestError {}
+
+impl Display for GuestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.panic {
+            Some(panic) => write!(f, "{panic}")?,
+            None => write!(f, "{}", self.trap)?,
+        }
+
+        for (i, frame) in self.backtrace.iter().enumerate() {
+            let function = frame.function.as_deref().unwrap_or("<unknown>");
+            write!(f, "\n{i:>4}: {function}")?;
+            if let Some(file) = &frame.file {
+                write!(f, "\n          at {file}")?;
+                if let Some(line) = frame.line {
+                    write!(f, ":{line}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub const PAGE_SIZE: usize = 65_536;
 
 #[derive(GetSize, Hash)]
@@ -1,15 +1,15 @@
 use std::{path::Path, sync::Arc};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use tracing::trace;
 use vg_asset::{Asset, AssetKind, Assets, BinAsset};
-use vg_interface::{DeBin, Request, Response, SerBin, WaitReason};
+use vg_interface::{DeBin, Request, Response, SerBin, WaitReason, ABI_VERSION};
 use wasi_common::WasiCtx;
 use wasmtime::*;
 use wasmtime_wasi::WasiCtxBuilder;
 
 use crate::{
-    executor::{GlobalData, MemoryData, TableData, PAGE_SIZE},
+    executor::{GlobalData, GuestError, GuestFrame, MemoryData, TableData, PAGE_SIZE},
     Provider,
 };
 
@@ -18,6 +18,8 @@ pub struct WasmtimeInner {
     wasi: WasiCtx,
     response: Vec<u8>,
     func: Box<dyn FnMut(Request) -> Response>,
+    /// Message of a panic during the current step
+    panic: Option<String>,
 }
 pub struct WasmtimeModule {
     engine: Engine,
@@ -25,14 +27,38 @@ pub struct WasmtimeModule {
 }
 
 impl WasmtimeModule {
+    /// Check the module exports what the runtime needs
+    fn validate(&self) -> Result<()> {
+        let is_func = |name| matches!(self.module.get_export(name), Some(ExternType::Func(_)));
+
+        if !is_func("__vg_abi_version") {
+            bail!(
+                "Module does not export __vg_abi_version. Is it a game built with \
+                 an up to date vg?"
+            );
+        }
+        if !is_func("__vg_step") {
+            bail!("Module does not export __vg_step");
+        }
+        let memory = self.module.get_export("memory");
+        if !matches!(memory, Some(ExternType::Memory(_))) {
+            bail!("Module does not export its memory as 'memory'");
+        }
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn instantiate(self: &Arc<Self>) -> Result<WasmtimeInstance> {
+        self.validate()?;
+
         let mut store = Store::new(
             &self.engine,
             WasmtimeInner {
                 wasi: WasiCtxBuilder::new().inherit_stdout().build(),
                 response: vec![],
                 func: Box::new(|_| unreachable!()),
+                panic: None,
             },
         );
 
@@ -54,9 +80,21 @@ impl WasmtimeModule {
                 let bytes = &mem.data(&caller)[ptr as usize..][..len as usize];
                 let request = Request::deserialize_bin(bytes)?;
 
-                // Call to engine implementation
-                let func = &mut caller.data_mut().func;
-                let response = (func)(request);
+                // Call to engine implementation. Panics are kept for the
+                // error that follows, batches are answered one by one
+                let response = match request {
+                    Request::Panic { message } => {
+                        caller.data_mut().panic = Some(message);
+                        Response::Empty
+                    }
+                    Request::Batch { requests } => {
+                        for request in requests {
+                            (caller.data_mut().func)(request);
+                        }
+                        Response::Empty
+                    }
+                    request => (caller.data_mut().func)(request),
+                };
 
                 // Store response for later fetch
                 caller.data_mut().response = response.serialize_bin();
@@ -83,8 +121,31 @@ impl WasmtimeModule {
             },
         )?;
 
+        // Anything else would only fail as a link error
+        for import in self.module.imports() {
+            let provided = linker.get(&mut store, import.module(), import.name());
+            if provided.is_none() {
+                bail!(
+                    "Module imports {}::{}, which the runtime does not provide",
+                    import.module(),
+                    import.name()
+                );
+            }
+        }
+
         let instance = linker.instantiate(&mut store, &self.module)?;
 
+        // Before any game code runs, as a mismatch breaks every request
+        let version = instance
+            .get_typed_func::<(), u32>(&mut store, "__vg_abi_version")?
+            .call(&mut store, ())?;
+        if version != ABI_VERSION {
+            bail!(
+                "Game is built for vg ABI version {version}, but the runtime \
+                 supports {ABI_VERSION}. Rebuild it against this version of vg"
+            );
+        }
+
         // Call default export (either "" or "_start")
         instance
             .get_typed_func(&mut store, "")
@@ -116,7 +177,9 @@ impl AssetKind for WasmtimeInstance {
     #[tracing::instrument(skip_all)]
     fn produce(data: &mut Self::Data) -> Option<Self> {
         let bin = data.get()?;
-        super::Instance::new(&bin.bytes, true).ok()
+        super::Instance::new(&bin.bytes, true)
+            .map_err(|error| tracing::error!("Failed to load game: {error:#}"))
+            .ok()
     }
 }
 
@@ -146,7 +209,7 @@ impl super::Instance for WasmtimeInstance {
     }
 
     #[tracing::instrument(skip_all)]
-    fn step<T: Provider>(&mut self, provider: &mut T) -> WaitReason {
+    fn step<T: Provider>(&mut self, provider: &mut T) -> Result<WaitReason, GuestError> {
         let ptr = provider as *mut T as *mut ();
 
         self.store.data_mut().func = Box::new(move |req| {
@@ -155,15 +218,24 @@ impl super::Instance for WasmtimeInstance {
             unsafe { (*provider).provide(req) }
         });
 
+        self.store.data_mut().panic = None;
+
         let func = self
             .instance
             .get_func(&mut self.store, "__vg_step")
-            .unwrap();
+            .ok_or_else(|| GuestError {
+                trap: "Module does not export __vg_step".into(),
+                panic: None,
+                backtrace: vec![],
+            })?;
 
         let mut ret = [Val::I32(0)];
-        func.call(&mut self.store, &[], &mut ret).unwrap();
+        if let Err(error) = func.call(&mut self.store, &[], &mut ret) {
+            let panic = self.store.data_mut().panic.take();
+            return Err(guest_error(error, panic));
+        }
 
-        WaitReason::from_raw(ret[0].unwrap_i32())
+        Ok(WaitReason::from_raw(ret[0].unwrap_i32()))
     }
 
     #[tracing::instrument(skip_all)]
@@ -261,6 +333,47 @@ impl super::Instance for WasmtimeInstance {
     }
 }
 
+/// Describe a failed call into the guest
+fn guest_error(error: Error, panic: Option<String>) -> GuestError {
+    // Errors from host functions are not traps, but stop the guest all the same
+    let trap = match error.downcast_ref::<Trap>() {
+        Some(trap) => trap.to_string(),
+        None => format!("{error:#}"),
+    };
+
+    // Inlined functions show up as extra symbols of the same frame
+    let backtrace = error
+        .downcast_ref::<WasmBacktrace>()
+        .map(|backtrace| {
+            backtrace
+                .frames()
+                .iter()
+                .flat_map(|frame| match frame.symbols() {
+                    [] => vec![GuestFrame {
+                        function: frame.func_name().map(String::from),
+                        file: None,
+                        line: None,
+                    }],
+                    symbols => symbols
+                        .iter()
+                        .map(|symbol| GuestFrame {
+                            function: symbol.name().map(String::from),
+                            file: symbol.file().map(String::from),
+                            line: symbol.line(),
+                        })
+                        .collect(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    GuestError {
+        trap,
+        panic,
+        backtrace,
+    }
+}
+
 impl GlobalData {
     fn as_val(&self) -> Val {
         match self {
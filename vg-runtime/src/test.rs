@@ -1,2 +1,110 @@
+use vg_interface::{Request, Response, ABI_VERSION};
+
+use crate::{
+    executor::{GuestError, GuestFrame, Instance, WasmInstance},
+    Provider,
+};
+
+/// For games that shouldn't make any requests
+struct NoRequests;
+
+impl Provider for NoRequests {
+    fn provide(&mut self, request: Request) -> Response {
+        panic!("Unexpected request {request:?}");
+    }
+}
+
 #[test]
 fn create_runtime() {}
+
+#[test]
+fn guest_error_report() {
+    let error = GuestError {
+        trap: "wasm trap: wasm `unreachable` instruction executed".into(),
+        panic: Some("panicked at src/main.rs:4:5:\nboom".into()),
+        backtrace: vec![
+            GuestFrame {
+                function: Some("my_game::update".into()),
+                file: Some("src/main.rs".into()),
+                line: Some(4),
+            },
+            GuestFrame {
+                function: None,
+                file: None,
+                line: None,
+            },
+        ],
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "panicked at src/main.rs:4:5:\nboom\
+         \n   0: my_game::update\
+         \n          at src/main.rs:4\
+         \n   1: <unknown>"
+    );
+}
+
+#[test]
+fn guest_trap() {
+    let wat = format!(
+        r#"(module
+            (memory (export "memory") 1)
+            (func (export "_start"))
+            (func (export "__vg_abi_version") (result i32) (i32.const {ABI_VERSION}))
+            (func $update unreachable)
+            (func $step (export "__vg_step") (result i32)
+                (call $update)
+                (i32.const 1)))"#
+    );
+    let mut instance = WasmInstance::new(wat.as_bytes(), true).unwrap();
+
+    let error = instance.step(&mut NoRequests).unwrap_err();
+    assert!(error.trap.contains("unreachable"), "{}", error.trap);
+    assert_eq!(error.panic, None);
+
+    // Innermost first, named from the name section
+    let functions: Vec<_> = error
+        .backtrace
+        .iter()
+        .map(|frame| frame.function.as_deref())
+        .collect();
+    assert_eq!(functions, [Some("update"), Some("step")]);
+}
+
+/// Error from loading a module in text format
+fn load_error(wat: &str) -> String {
+    match WasmInstance::new(wat.as_bytes(), false) {
+        Ok(_) => panic!("Module loaded"),
+        Err(error) => format!("{error:#}"),
+    }
+}
+
+/// Module exporting what a game must, with extra items
+fn game_module(version: u32, extra: &str) -> String {
+    format!(
+        r#"(module
+            {extra}
+            (memory (export "memory") 1)
+            (func (export "_start"))
+            (func (export "__vg_abi_version") (result i32) (i32.const {version}))
+            (func (export "__vg_step") (result i32) (i32.const 1)))"#
+    )
+}
+
+#[test]
+fn abi_handshake() {
+    assert!(WasmInstance::new(game_module(ABI_VERSION, "").as_bytes(), false).is_ok());
+
+    let error = load_error(&game_module(ABI_VERSION + 1, ""));
+    assert!(error.contains("ABI version"), "{error}");
+
+    let error = load_error(&game_module(
+        ABI_VERSION,
+        r#"(import "env" "__vg_teleport" (func))"#,
+    ));
+    assert!(error.contains("env::__vg_teleport"), "{error}");
+
+    let error = load_error(r#"(module (memory (export "memory") 1) (func (export "_start")))"#);
+    assert!(error.contains("__vg_abi_version"), "{error}");
+}
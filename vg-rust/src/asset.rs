@@ -0,0 +1,72 @@
+//! Files from the engine's asset server, which reloads them as they change
+//!
+//! Loading waits for the file without blocking the engine. While nothing else
+//! presents, ticks end without a new frame. The engine records the contents it
+//! hands out, so rollbacks and replays see the same bytes. Typed JSON values
+//! use nanoserde, so derive `DeJson` on them
+
+use vg_interface::{DeJson, Request, WaitReason};
+
+use crate::{executor::wait, ffi, Vec2, Vec4};
+
+/// Raw contents of a file. Waits until the file has loaded
+pub async fn load(path: impl Into<String>) -> Vec<u8> {
+    let path = path.into();
+    loop {
+        let request = Request::LoadAsset { path: path.clone() };
+        if let Some(bytes) = ffi::dispatch(request).unwrap_asset() {
+            return bytes;
+        }
+
+        // Ask again next tick
+        wait(WaitReason::Pending).await;
+    }
+}
+
+/// Contents of a text file. None if it isn't valid UTF-8
+pub async fn load_text(path: impl Into<String>) -> Option<String> {
+    String::from_utf8(load(path).await).ok()
+}
+
+/// Parse a JSON file. None if it can't be read as `T`
+pub async fn load_json<T: DeJson>(path: impl Into<String>) -> Option<T> {
+    T::deserialize_json(&load_text(path).await?).ok()
+}
+
+/// Decode a PNG image. None if it isn't one
+pub async fn load_image(path: impl Into<String>) -> Option<Image> {
+    let image = image::load_from_memory(&load(path).await)
+        .ok()?
+        .into_rgba8();
+
+    Some(Image {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    })
+}
+
+/// Image decoded into 8-bit RGBA pixels, row by row
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Image {
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// Color of a pixel, None if outside the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Vec4> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = (y * self.width + x) as usize * 4;
+        let channels = std::array::from_fn(|c| self.rgba[i + c] as f32);
+        Some(Vec4::from_array(channels) / 255.0)
+    }
+}
@@ -0,0 +1,63 @@
+use vg_interface::Request;
+
+use crate::ffi;
+
+/// Volume, panning and looping of a sound. Defaults to full volume, centered
+/// and played once
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoundStyle {
+    volume: f32,
+    pan: f32,
+    looping: bool,
+}
+
+impl SoundStyle {
+    pub fn new() -> SoundStyle {
+        SoundStyle {
+            volume: 1.0,
+            pan: 0.0,
+            looping: false,
+        }
+    }
+
+    /// Gain multiplied with the samples
+    pub fn volume(mut self, volume: f32) -> SoundStyle {
+        self.volume = volume;
+        self
+    }
+
+    /// Position from -1 (left) to 1 (right)
+    pub fn pan(mut self, pan: f32) -> SoundStyle {
+        self.pan = pan;
+        self
+    }
+
+    /// Start over from the beginning when the end is reached
+    pub fn looping(mut self, looping: bool) -> SoundStyle {
+        self.looping = looping;
+        self
+    }
+}
+
+impl Default for SoundStyle {
+    fn default() -> Self {
+        SoundStyle::new()
+    }
+}
+
+/// Play a WAV or Ogg Vorbis asset once
+pub fn play_sound(path: impl Into<String>) {
+    play_sound_with(path, SoundStyle::new());
+}
+
+/// Play a sound asset. It starts once loaded. Sounds follow rollback, so a
+/// re-simulated tick does not play its sounds twice
+pub fn play_sound_with(path: impl Into<String>, style: SoundStyle) {
+    ffi::dispatch(Request::PlaySound {
+        path: path.into(),
+        volume: style.volume,
+        pan: style.pan,
+        looping: style.looping,
+    })
+    .unwrap_empty();
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+
+use vg_interface::Request;
+
+use crate::{ffi, screen_size, Mat3, Vec2};
+
+thread_local! {
+    /// Lives in guest memory, so it is rolled back along with the game
+    static CAMERAS: RefCell<Vec<Camera>> = const { RefCell::new(vec![]) };
+}
+
+/// 2D camera showing the world on part of the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// World position shown at the center of the viewport
+    pub position: Vec2,
+    /// Screen units per world unit
+    pub zoom: f32,
+    /// Rotation of the camera in radians. The world turns the other way
+    pub rotation: f32,
+    /// Area of the screen drawn to as (min, max), in fractions of its size
+    pub viewport: (Vec2, Vec2),
+}
+
+impl Camera {
+    /// Camera looking at a world position, covering the whole screen
+    pub fn new(position: Vec2) -> Camera {
+        Camera {
+            position,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: (Vec2::ZERO, Vec2::ONE),
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Camera {
+        self.position = position;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Camera {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Camera {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Only draw to part of the screen, given in fractions of its size
+    pub fn with_viewport(mut self, min: Vec2, max: Vec2) -> Camera {
+        self.viewport = (min, max);
+        self
+    }
+
+    /// Maps world positions onto the screen
+    pub fn matrix(&self) -> Mat3 {
+        let t = vg_interface::Camera::from(*self).transform(screen_size().into());
+        Mat3::from_cols(
+            Vec2::from(t.x_axis).extend(0.0),
+            Vec2::from(t.y_axis).extend(0.0),
+            Vec2::from(t.translation).extend(1.0),
+        )
+    }
+
+    pub fn world_to_screen(&self, position: Vec2) -> Vec2 {
+        self.matrix().transform_point2(position)
+    }
+
+    /// Find what is under the cursor, for example
+    pub fn screen_to_world(&self, position: Vec2) -> Vec2 {
+        self.matrix().inverse().transform_point2(position)
+    }
+}
+
+impl From<Camera> for vg_interface::Camera {
+    fn from(camera: Camera) -> Self {
+        vg_interface::Camera {
+            position: camera.position.into(),
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+            viewport: (camera.viewport.0.into(), camera.viewport.1.into()),
+        }
+    }
+}
+
+/// The first camera. Without any, a camera showing the world as is
+pub fn camera() -> Camera {
+    CAMERAS
+        .with(|cameras| cameras.borrow().first().copied())
+        .unwrap_or_else(|| Camera::new(screen_size() / 2.0))
+}
+
+/// All cameras, in drawing order
+pub fn cameras() -> Vec<Camera> {
+    CAMERAS.with(|cameras| cameras.borrow().clone())
+}
+
+/// Show the world through a single camera
+pub fn set_camera(camera: Camera) {
+    set_cameras([camera]);
+}
+
+/// Show the world through several cameras, each drawing to its own viewport.
+/// Used for split screen
+pub fn set_cameras(cameras: impl IntoIterator<Item = Camera>) {
+    let cameras: Vec<Camera> = cameras.into_iter().collect();
+    ffi::dispatch(Request::SetCameras {
+        cameras: cameras.iter().copied().map(Into::into).collect(),
+    })
+    .unwrap_empty();
+    CAMERAS.with(|c| *c.borrow_mut() = cameras);
+}
+
+/// Go back to drawing the world directly in screen units
+pub fn reset_camera() {
+    set_cameras([]);
+}
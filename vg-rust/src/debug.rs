@@ -0,0 +1,37 @@
+//! Gizmos for development, like collision boxes, paths and velocities
+//!
+//! They are drawn like anything else, but only shown while the engine's debug
+//! overlay is on. Release builds of the game leave them out. Outlines are one
+//! pixel wide regardless of zoom
+
+use crate::{draw, Stroke, Style, TextStyle, Vec2, Vec4};
+
+/// Make every draw within `f` a gizmo, for anything the functions here don't
+/// cover
+pub fn with_debug<T>(f: impl FnOnce() -> T) -> T {
+    draw::with_debug(f)
+}
+
+/// Line between two points
+pub fn line(from: Vec2, to: Vec2, color: Vec4) {
+    with_debug(|| draw::line(outline(color), [from, to]));
+}
+
+/// Outline of a circle
+pub fn circle(center: Vec2, radius: f32, color: Vec4) {
+    with_debug(|| draw::circle(center, radius, Style::stroke(outline(color))));
+}
+
+/// Outline of an axis aligned rectangle between two corners
+pub fn rect(min: Vec2, max: Vec2, color: Vec4) {
+    with_debug(|| draw::rect(min, max, Style::stroke(outline(color))));
+}
+
+/// Text with its top left corner at `position`
+pub fn text(position: Vec2, text: impl Into<String>, color: Vec4) {
+    with_debug(|| draw::text(position, text, TextStyle::new(color)));
+}
+
+fn outline(color: Vec4) -> Stroke {
+    Stroke::new(color).view_width(1.0)
+}
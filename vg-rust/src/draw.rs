@@ -0,0 +1,410 @@
+use std::cell::Cell;
+
+use vg_interface::{Align, Cap, Draw, Join, PathSegment, Request, Space};
+
+use crate::{ffi, transform, Mat3, Paint, Vec2, Vec4, WHITE};
+
+thread_local! {
+    /// Space of the draws made right now
+    static SPACE: Cell<Space> = const { Cell::new(Space::World) };
+    /// Draws made right now are debug gizmos
+    static DEBUG: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Draw in view space within `f`, fixed on the canvas regardless of cameras
+/// and over the world. Meant for UI
+pub fn with_view_space<T>(f: impl FnOnce() -> T) -> T {
+    let previous = SPACE.with(|space| space.replace(Space::View));
+    let result = f();
+    SPACE.with(|space| space.set(previous));
+    result
+}
+
+/// Make every draw within `f` a debug gizmo
+pub(crate) fn with_debug<T>(f: impl FnOnce() -> T) -> T {
+    let previous = DEBUG.with(|debug| debug.replace(true));
+    let result = f();
+    DEBUG.with(|debug| debug.set(previous));
+    result
+}
+
+/// Fill and stroke of a shape. A plain color or paint converts into a fill
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    pub fill: Option<Paint>,
+    pub stroke: Option<Stroke>,
+}
+
+impl Style {
+    /// Only fill the shape
+    pub fn fill(paint: impl Into<Paint>) -> Style {
+        Style {
+            fill: Some(paint.into()),
+            stroke: None,
+        }
+    }
+
+    /// Only outline the shape
+    pub fn stroke(stroke: impl Into<Stroke>) -> Style {
+        Style {
+            fill: None,
+            stroke: Some(stroke.into()),
+        }
+    }
+
+    pub fn with_fill(mut self, paint: impl Into<Paint>) -> Style {
+        self.fill = Some(paint.into());
+        self
+    }
+
+    pub fn with_stroke(mut self, stroke: impl Into<Stroke>) -> Style {
+        self.stroke = Some(stroke.into());
+        self
+    }
+}
+
+impl From<Vec4> for Style {
+    fn from(color: Vec4) -> Style {
+        Style::fill(color)
+    }
+}
+
+impl From<Paint> for Style {
+    fn from(paint: Paint) -> Style {
+        Style::fill(paint)
+    }
+}
+
+impl From<Style> for vg_interface::Style {
+    fn from(style: Style) -> Self {
+        vg_interface::Style {
+            fill: style.fill.map(Into::into),
+            stroke: style.stroke.map(Into::into),
+        }
+    }
+}
+
+/// Outline style for lines and shapes. A plain color or paint converts into a
+/// solid one unit wide stroke
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroke {
+    paint: Paint,
+    width: f32,
+    space: Space,
+    cap: Cap,
+    join: Join,
+    miter_limit: f32,
+    dashes: Vec<f32>,
+    dash_offset: f32,
+}
+
+impl Stroke {
+    pub fn new(paint: impl Into<Paint>) -> Stroke {
+        // Only the settings are taken from here
+        let default = vg_interface::Stroke::new(vg_interface::Paint::Solid(Default::default()));
+        Stroke {
+            paint: paint.into(),
+            width: default.width,
+            space: default.space,
+            cap: default.cap,
+            join: default.join,
+            miter_limit: default.miter_limit,
+            dashes: default.dashes,
+            dash_offset: default.dash_offset,
+        }
+    }
+
+    /// Width in world units, scaled by the current transform
+    pub fn width(mut self, width: f32) -> Stroke {
+        self.width = width;
+        self.space = Space::World;
+        self
+    }
+
+    /// Width in screen pixels, regardless of the current transform
+    pub fn view_width(mut self, width: f32) -> Stroke {
+        self.width = width;
+        self.space = Space::View;
+        self
+    }
+
+    /// Shape of the line ends
+    pub fn cap(mut self, cap: Cap) -> Stroke {
+        self.cap = cap;
+        self
+    }
+
+    /// Shape of the corners
+    pub fn join(mut self, join: Join) -> Stroke {
+        self.join = join;
+        self
+    }
+
+    /// Ratio of miter length to width before a miter join turns into a bevel
+    pub fn miter_limit(mut self, limit: f32) -> Stroke {
+        self.miter_limit = limit;
+        self
+    }
+
+    /// Alternating lengths of dashes and gaps, starting `offset` units into
+    /// the pattern
+    pub fn dashes(mut self, offset: f32, pattern: impl IntoIterator<Item = f32>) -> Stroke {
+        self.dash_offset = offset;
+        self.dashes = pattern.into_iter().collect();
+        self
+    }
+}
+
+impl From<Vec4> for Stroke {
+    fn from(color: Vec4) -> Stroke {
+        Stroke::new(color)
+    }
+}
+
+impl From<Paint> for Stroke {
+    fn from(paint: Paint) -> Stroke {
+        Stroke::new(paint)
+    }
+}
+
+impl From<Stroke> for vg_interface::Stroke {
+    fn from(stroke: Stroke) -> Self {
+        vg_interface::Stroke {
+            paint: stroke.paint.into(),
+            width: stroke.width,
+            space: stroke.space,
+            cap: stroke.cap,
+            join: stroke.join,
+            miter_limit: stroke.miter_limit,
+            dashes: stroke.dashes,
+            dash_offset: stroke.dash_offset,
+        }
+    }
+}
+
+/// Font, size, paint and alignment of text. A plain color or paint converts
+/// into the default font, 16 units tall and left aligned. Paint positions are
+/// relative to the text position
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    paint: Paint,
+    size: f32,
+    font: Option<String>,
+    align: Align,
+}
+
+impl TextStyle {
+    pub fn new(paint: impl Into<Paint>) -> TextStyle {
+        TextStyle {
+            paint: paint.into(),
+            size: 16.0,
+            font: None,
+            align: Align::Left,
+        }
+    }
+
+    pub fn size(mut self, size: f32) -> TextStyle {
+        self.size = size;
+        self
+    }
+
+    /// Asset path of a TrueType or OpenType font
+    pub fn font(mut self, path: impl Into<String>) -> TextStyle {
+        self.font = Some(path.into());
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> TextStyle {
+        self.align = align;
+        self
+    }
+}
+
+impl From<Vec4> for TextStyle {
+    fn from(color: Vec4) -> TextStyle {
+        TextStyle::new(color)
+    }
+}
+
+impl From<Paint> for TextStyle {
+    fn from(paint: Paint) -> TextStyle {
+        TextStyle::new(paint)
+    }
+}
+
+/// Source region and tint of a sprite. Defaults to the whole image, untinted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageStyle {
+    src: Option<(Vec2, Vec2)>,
+    tint: Vec4,
+}
+
+impl ImageStyle {
+    pub fn new() -> ImageStyle {
+        ImageStyle {
+            src: None,
+            tint: WHITE,
+        }
+    }
+
+    /// Only draw the part of the image between two corners, in pixels
+    pub fn src(mut self, min: Vec2, max: Vec2) -> ImageStyle {
+        self.src = Some((min, max));
+        self
+    }
+
+    /// Color multiplied with the image
+    pub fn tint(mut self, color: Vec4) -> ImageStyle {
+        self.tint = color;
+        self
+    }
+}
+
+impl Default for ImageStyle {
+    fn default() -> Self {
+        ImageStyle::new()
+    }
+}
+
+/// Builder for arbitrary shapes made out of lines and Bézier curves
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path::default()
+    }
+
+    /// Begin a new subpath at a point
+    pub fn move_to(mut self, to: Vec2) -> Path {
+        self.segments.push(PathSegment::MoveTo { to: to.into() });
+        self
+    }
+
+    pub fn line_to(mut self, to: Vec2) -> Path {
+        self.segments.push(PathSegment::LineTo { to: to.into() });
+        self
+    }
+
+    /// Quadratic Bézier curve
+    pub fn quad_to(mut self, control: Vec2, to: Vec2) -> Path {
+        self.segments.push(PathSegment::QuadTo {
+            control: control.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Cubic Bézier curve
+    pub fn cubic_to(mut self, control1: Vec2, control2: Vec2, to: Vec2) -> Path {
+        self.segments.push(PathSegment::CubicTo {
+            control1: control1.into(),
+            control2: control2.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Connect back to the start of the current subpath
+    pub fn close(mut self) -> Path {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+}
+
+fn draw(draw: Draw) {
+    let transform = transform::to_interface(transform::transform());
+    let space = SPACE.with(Cell::get);
+
+    // Draws are only seen once the tick is done, so they can wait. Gizmos
+    // don't make it into release builds
+    match DEBUG.with(Cell::get) {
+        false => ffi::queue(Request::Draw {
+            transform,
+            space,
+            draw,
+        }),
+        true if cfg!(debug_assertions) => ffi::queue(Request::DebugDraw {
+            transform,
+            space,
+            draw,
+        }),
+        true => (),
+    }
+}
+
+/// Open polyline through all the points
+pub fn line(stroke: impl Into<Stroke>, points: impl IntoIterator<Item = Vec2>) {
+    draw(Draw::Line {
+        stroke: stroke.into().into(),
+        points: points.into_iter().map(Into::into).collect(),
+    });
+}
+
+/// Axis aligned rectangle between two corners
+pub fn rect(min: Vec2, max: Vec2, style: impl Into<Style>) {
+    draw(Draw::Rect {
+        min: min.into(),
+        max: max.into(),
+        style: style.into().into(),
+    });
+}
+
+pub fn circle(center: Vec2, radius: f32, style: impl Into<Style>) {
+    ellipse(center, Vec2::splat(radius), style);
+}
+
+pub fn ellipse(center: Vec2, radii: Vec2, style: impl Into<Style>) {
+    draw(Draw::Ellipse {
+        center: center.into(),
+        radii: radii.into(),
+        style: style.into().into(),
+    });
+}
+
+/// Closed shape through all the points
+pub fn polygon(points: impl IntoIterator<Item = Vec2>, style: impl Into<Style>) {
+    draw(Draw::Polygon {
+        points: points.into_iter().map(Into::into).collect(),
+        style: style.into().into(),
+    });
+}
+
+pub fn path(path: &Path, style: impl Into<Style>) {
+    draw(Draw::Path {
+        segments: path.segments.clone(),
+        style: style.into().into(),
+    });
+}
+
+/// Draw text with its top edge at `position`. Lines are split on newlines
+pub fn text(position: Vec2, text: impl Into<String>, style: impl Into<TextStyle>) {
+    let style = style.into();
+    draw(Draw::Text {
+        text: text.into(),
+        font: style.font,
+        size: style.size,
+        paint: style.paint.into(),
+        position: position.into(),
+        align: style.align,
+    });
+}
+
+/// Draw an image asset with its top left corner at `position`, one image
+/// pixel per unit
+pub fn image(path: impl Into<String>, position: Vec2) {
+    sprite(path, Mat3::from_translation(position), ImageStyle::new());
+}
+
+/// Draw an image asset, placing its pixels with a transform. The image is
+/// skipped until it has loaded
+pub fn sprite(path: impl Into<String>, transform: Mat3, style: ImageStyle) {
+    draw(Draw::Image {
+        path: path.into(),
+        src_rect: style.src.map(|(min, max)| (min.into(), max.into())),
+        dst_transform: transform::to_interface(transform),
+        tint: style.tint.into(),
+    });
+}
@@ -1,85 +1,219 @@
 use std::{
     cell::{Cell, RefCell},
-    rc::Rc,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+    thread::LocalKey,
 };
 
-use async_notify::Notify;
 use futures_channel::oneshot;
-use futures_executor::LocalPool;
-use futures_util::task::LocalSpawnExt;
-use vg_interface::WaitReason;
+use futures_executor::{LocalPool, LocalSpawner};
+use futures_util::{
+    future::{select, AbortHandle, Abortable, Either},
+    task::LocalSpawnExt,
+};
+use vg_interface::{Request, WaitReason};
+
+use crate::ffi;
 
 use std::future::Future;
 
 thread_local! {
     static POOL: RefCell<LocalPool> = RefCell::new(LocalPool::new());
-    static STALL: Rc<Notify> = Rc::new(Notify::new());
+    /// Spawns onto the pool, even while it is running tasks
+    static SPAWNER: LocalSpawner = POOL.with(|pool| pool.borrow().spawner());
     static WAIT_REASON: Cell<WaitReason> = Cell::new(WaitReason::Startup);
+    /// Step during which the wait reason was last set
+    static REASON_STEP: Cell<u64> = const { Cell::new(0) };
+    static EXITED: Cell<bool> = const { Cell::new(false) };
+    /// Steps and ticks started so far, along with the tasks waiting for the
+    /// next one
+    static STEPS: Cell<u64> = const { Cell::new(0) };
+    static STEP_WAKERS: RefCell<Vec<Waker>> = const { RefCell::new(vec![]) };
+    static TICKS: Cell<u64> = const { Cell::new(0) };
+    static TICK_WAKERS: RefCell<Vec<Waker>> = const { RefCell::new(vec![]) };
 }
 
 /// Yield into the vg runtime, continuing on the next step
 pub async fn wait(reason: WaitReason) {
-    let notify = STALL.with(|n| n.clone());
+    set_reason(reason);
+    Advance::new(&STEPS, &STEP_WAKERS).await;
+}
+
+/// Choose what ends the current step. A task presenting wins over others that
+/// are only pending during the same step
+fn set_reason(reason: WaitReason) {
+    let step = STEPS.with(Cell::get);
+    let presented = WAIT_REASON.with(Cell::get).is_present() && REASON_STEP.with(Cell::get) == step;
+    if reason.is_pending() && presented {
+        return;
+    }
+
     WAIT_REASON.with(|c| c.set(reason));
-    notify.notified().await;
+    REASON_STEP.with(|c| c.set(step));
+}
+
+/// Wait for a number of ticks to pass. Meant for coroutines running alongside
+/// the main loop, which still decides whether the ticks present a frame
+pub async fn sleep_ticks(ticks: u64) {
+    for _ in 0..ticks {
+        Advance::new(&TICKS, &TICK_WAKERS).await;
+    }
+}
+
+/// Run a future for up to some number of ticks. None if it didn't finish in
+/// time, in which case it is dropped
+pub async fn timeout<T>(ticks: u64, future: impl Future<Output = T>) -> Option<T> {
+    // Select favors the first future, so finishing on the last tick counts
+    match select(pin!(future), pin!(sleep_ticks(ticks))).await {
+        Either::Left((value, _)) => Some(value),
+        Either::Right(_) => None,
+    }
 }
 
 /// Start the main future, wrapping it in an exit handler
 #[doc(hidden)]
 pub fn start(future: impl Future<Output = ()> + 'static) {
+    // Panics trap the instance, tell the runtime why before that happens
+    std::panic::set_hook(Box::new(|info| {
+        ffi::dispatch(Request::Panic {
+            message: info.to_string(),
+        });
+    }));
+
     spawn(async {
         // Notify the runtime that we are ready to execute
         wait(WaitReason::Startup).await;
 
         future.await;
 
-        // DEBUG
-        println!("Clean exit");
+        // Returning from main is an exit, unless one was asked for already
+        exit(0);
     });
 }
 
+/// Stop the game with an exit code. Code runs until the next await, after
+/// which the game is never stepped again
+pub fn exit(code: i32) {
+    if EXITED.with(|e| e.replace(true)) {
+        return;
+    }
+    ffi::dispatch(Request::Exit { code });
+}
+
 /// Execute a step
 #[doc(hidden)]
 pub fn step() -> WaitReason {
-    // Clear the runtime yield
-    STALL.with(|notify| notify.notify());
+    // The previous step ended a tick, so this one starts a new one
+    if WAIT_REASON.with(Cell::get).ends_tick() {
+        Advance::advance(&TICKS, &TICK_WAKERS);
+    }
+    Advance::advance(&STEPS, &STEP_WAKERS);
 
     // Run until something stalls the runtime again
     POOL.with(|pool| pool.borrow_mut().run_until_stalled());
-    WAIT_REASON.with(Cell::get)
+    ffi::flush();
+
+    // No task chose how the step ends, so the tick ends without a new frame
+    if REASON_STEP.with(Cell::get) != STEPS.with(Cell::get) {
+        set_reason(WaitReason::Pending);
+    }
+
+    match EXITED.with(Cell::get) {
+        true => WaitReason::Exit,
+        false => WAIT_REASON.with(Cell::get),
+    }
+}
+
+/// Resolves once a counter has moved past its value at the first poll
+struct Advance {
+    counter: &'static LocalKey<Cell<u64>>,
+    wakers: &'static LocalKey<RefCell<Vec<Waker>>>,
+    start: Option<u64>,
+}
+
+impl Advance {
+    fn new(
+        counter: &'static LocalKey<Cell<u64>>,
+        wakers: &'static LocalKey<RefCell<Vec<Waker>>>,
+    ) -> Advance {
+        Advance {
+            counter,
+            wakers,
+            start: None,
+        }
+    }
+
+    /// Move a counter forward, waking everyone waiting on it in order
+    fn advance(
+        counter: &'static LocalKey<Cell<u64>>,
+        wakers: &'static LocalKey<RefCell<Vec<Waker>>>,
+    ) {
+        counter.with(|c| c.set(c.get() + 1));
+        wakers.with(RefCell::take).into_iter().for_each(Waker::wake);
+    }
 }
 
-/// Handle to a spawned future that can be joined by awaiting
+impl Future for Advance {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let now = this.counter.with(Cell::get);
+        if now > *this.start.get_or_insert(now) {
+            return Poll::Ready(());
+        }
+
+        this.wakers
+            .with(|w| w.borrow_mut().push(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+/// Handle to a spawned future that can be joined by awaiting. Gives the output
+/// of the future, or None if it was aborted
 pub struct JoinHandle<T> {
     receiver: oneshot::Receiver<T>,
+    abort: AbortHandle,
+}
+
+impl<T> JoinHandle<T> {
+    /// Cancel the task, dropping it at its current await
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
 }
 
 impl<T> Future for JoinHandle<T> {
-    type Output = T;
+    type Output = Option<T>;
 
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let receiver = &mut self.get_mut().receiver;
         futures_util::pin_mut!(receiver);
-        receiver.poll(cx).map(|r| r.expect("uh oh?"))
+        // The sender is dropped without sending when the task is aborted
+        receiver.poll(cx).map(Result::ok)
     }
 }
 
 /// Spawn a future that will execute asynchronously, optionally awaiting on the JoinHandle
 pub fn spawn<T: 'static>(future: impl Future<Output = T> + 'static) -> JoinHandle<T> {
     let (sender, receiver) = oneshot::channel();
+    let (abort, registration) = AbortHandle::new_pair();
+
+    let task = Abortable::new(
+        async {
+            // Caller is free to drop the join handle if they don't care about it
+            let _ = sender.send(future.await);
+        },
+        registration,
+    );
 
-    POOL.with(|pool| {
-        pool.borrow()
-            .spawner()
+    SPAWNER.with(|spawner| {
+        spawner
             .spawn_local(async {
-                // Caller is free to drop the join handle if they don't care about it
-                let _ = sender.send(future.await);
+                let _ = task.await;
             })
             .unwrap();
     });
 
-    JoinHandle { receiver }
+    JoinHandle { receiver, abort }
 }
@@ -1,7 +1,14 @@
+use std::cell::RefCell;
+
 use vg_interface::*;
 
 use crate::executor::step;
 
+thread_local! {
+    /// Requests waiting to be sent together
+    static QUEUE: RefCell<Vec<Request>> = const { RefCell::new(vec![]) };
+}
+
 extern "C" {
     /// Perform a Request to the runtime, returning the number of bytes required for deserializing the Response
     fn __vg_request(ptr: i32, len: i32) -> i32;
@@ -9,6 +16,13 @@ extern "C" {
     fn __vg_response(ptr: i32);
 }
 
+/// Protocol version this game was built with, checked by the runtime before
+/// anything else runs
+#[no_mangle]
+pub extern "C" fn __vg_abi_version() -> u32 {
+    ABI_VERSION
+}
+
 /// Advance the internal future until it stalls
 #[no_mangle]
 pub extern "C" fn __vg_step() -> WaitReason {
@@ -27,3 +41,16 @@ pub fn dispatch(req: Request) -> Response {
 
     Response::deserialize_bin(&buf).expect("Runtime gave malformed Response")
 }
+
+/// Send a request that doesn't need an answer along with the next flush
+pub fn queue(req: Request) {
+    QUEUE.with(|q| q.borrow_mut().push(req));
+}
+
+/// Send all queued requests in one batch
+pub fn flush() {
+    let requests = QUEUE.with(RefCell::take);
+    if !requests.is_empty() {
+        dispatch(Request::Batch { requests }).unwrap_empty();
+    }
+}
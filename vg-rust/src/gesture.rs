@@ -0,0 +1,140 @@
+use vg_interface::{Touch, TouchPhase};
+
+use crate::Vec2;
+
+/// How far a finger may wander before a touch stops being a tap, in the units
+/// of touch positions
+const TAP_SLOP: f32 = 10.0;
+/// How many ticks a finger may stay down for a touch to be a tap
+const TAP_TICKS: u32 = 20;
+
+/// Gesture recognized from the touches of one tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    /// A finger was lifted shortly after touching, without moving much
+    Tap { position: Vec2 },
+    /// A single finger moved. `delta` is the movement since the previous tick
+    Drag {
+        start: Vec2,
+        position: Vec2,
+        delta: Vec2,
+        /// The finger was lifted, this is the last update of the drag
+        ended: bool,
+    },
+    /// Two fingers moved. `scale` is the change in distance between them
+    /// since the previous tick
+    Pinch { center: Vec2, scale: f32 },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Tracked {
+    id: u64,
+    start: Vec2,
+    position: Vec2,
+    /// Moved too far to be a tap
+    moved: bool,
+    ticks: u32,
+}
+
+/// Recognizes gestures from the raw touch stream. Keep one around in your
+/// game state and update it once every tick
+///
+/// ```ignore
+/// for gesture in gestures.update(&vg::touches()) {
+///     if let Gesture::Pinch { scale, .. } = gesture {
+///         zoom *= scale;
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Gestures {
+    tracked: Vec<Tracked>,
+}
+
+impl Gestures {
+    pub fn new() -> Gestures {
+        Gestures::default()
+    }
+
+    /// Feed the touches of a new tick, returning the gestures they form
+    pub fn update(&mut self, touches: &[Touch]) -> Vec<Gesture> {
+        let previous = self.tracked.clone();
+        let previous_position = |id| {
+            previous
+                .iter()
+                .find(|t: &&Tracked| t.id == id)
+                .map(|t| t.position)
+        };
+
+        for touch in touches {
+            let position = Vec2::from(touch.position);
+            let tracked = match self.tracked.iter_mut().find(|t| t.id == touch.id) {
+                Some(tracked) => tracked,
+                None => {
+                    self.tracked.push(Tracked {
+                        id: touch.id,
+                        start: position,
+                        position,
+                        moved: false,
+                        ticks: 0,
+                    });
+                    self.tracked.last_mut().unwrap()
+                }
+            };
+            tracked.position = position;
+            tracked.ticks += 1;
+            tracked.moved |= tracked.start.distance(position) > TAP_SLOP;
+        }
+
+        let mut gestures = vec![];
+        let tracked = |id| *self.tracked.iter().find(|t| t.id == id).unwrap();
+
+        for touch in touches {
+            let t = tracked(touch.id);
+            if touch.phase == TouchPhase::Ended && !t.moved && t.ticks <= TAP_TICKS {
+                gestures.push(Gesture::Tap {
+                    position: t.position,
+                });
+            }
+        }
+
+        match touches {
+            [touch] => {
+                let t = tracked(touch.id);
+                let last = previous_position(touch.id).unwrap_or(t.start);
+                if t.moved && touch.phase != TouchPhase::Cancelled {
+                    gestures.push(Gesture::Drag {
+                        start: t.start,
+                        position: t.position,
+                        delta: t.position - last,
+                        ended: touch.phase == TouchPhase::Ended,
+                    });
+                }
+            }
+            [a, b] => {
+                let (a, b) = (tracked(a.id), tracked(b.id));
+                if let (Some(last_a), Some(last_b)) =
+                    (previous_position(a.id), previous_position(b.id))
+                {
+                    let last = last_a.distance(last_b);
+                    if last > 0.0 {
+                        gestures.push(Gesture::Pinch {
+                            center: (a.position + b.position) / 2.0,
+                            scale: a.position.distance(b.position) / last,
+                        });
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        // Forget fingers that are gone
+        self.tracked.retain(|t| {
+            touches
+                .iter()
+                .any(|touch| touch.id == t.id && touch.phase.is_active())
+        });
+
+        gestures
+    }
+}
@@ -0,0 +1,66 @@
+use vg_interface::{Gamepad, Input, Key, MouseButton, Request, Touch};
+
+use crate::{ffi, Vec2};
+
+/// Everything the player did, as seen by this tick
+pub fn input() -> Input {
+    ffi::dispatch(Request::Input).unwrap_input()
+}
+
+/// Is the key held down
+pub fn key_down(key: Key) -> bool {
+    input().keys_down.contains(&key)
+}
+
+/// Was the key pressed since the previous tick
+pub fn key_pressed(key: Key) -> bool {
+    input().keys_pressed.contains(&key)
+}
+
+/// Was the key released since the previous tick
+pub fn key_released(key: Key) -> bool {
+    input().keys_released.contains(&key)
+}
+
+/// Cursor position in virtual units when a virtual resolution is set,
+/// otherwise in surface pixels. None if outside the window
+pub fn mouse_pos() -> Option<Vec2> {
+    input().cursor.map(Into::into)
+}
+
+/// Is the mouse button held down
+pub fn mouse_down(button: MouseButton) -> bool {
+    input().buttons_down.contains(&button)
+}
+
+/// Was the mouse button pressed since the previous tick
+pub fn mouse_pressed(button: MouseButton) -> bool {
+    input().buttons_pressed.contains(&button)
+}
+
+/// Was the mouse button released since the previous tick
+pub fn mouse_released(button: MouseButton) -> bool {
+    input().buttons_released.contains(&button)
+}
+
+/// Scrolled amount since the previous tick, in lines
+pub fn mouse_wheel() -> Vec2 {
+    input().wheel.into()
+}
+
+/// Every gamepad seen during this session, including disconnected ones.
+/// A pad keeps its index if it reconnects
+pub fn gamepads() -> Vec<Gamepad> {
+    input().gamepads
+}
+
+/// Gamepad by its index, if it has ever been connected
+pub fn gamepad(index: u32) -> Option<Gamepad> {
+    gamepads().into_iter().find(|pad| pad.index == index)
+}
+
+/// Fingers on the screen during this tick, and ones lifted since the previous
+/// tick. Positions are in the same units as `mouse_pos`
+pub fn touches() -> Vec<Touch> {
+    input().touches
+}
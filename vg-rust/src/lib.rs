@@ -1,16 +1,56 @@
 #![feature(fn_traits, unboxed_closures)]
 
+pub mod asset;
+mod audio;
+mod camera;
 mod consts;
+pub mod debug;
+mod draw;
 mod executor;
 mod ffi;
+mod gesture;
+mod input;
+pub mod log;
 mod math;
+mod object;
+mod paint;
+pub mod rand;
+pub mod storage;
+mod time;
+mod transform;
+mod window;
 
+pub use asset::load;
+pub use audio::{play_sound, play_sound_with, SoundStyle};
+pub use camera::{camera, cameras, reset_camera, set_camera, set_cameras, Camera};
 pub use consts::*;
-pub use executor::{spawn, start, wait, JoinHandle};
+pub use draw::{
+    circle, ellipse, image, line, path, polygon, rect, sprite, text, with_view_space, ImageStyle,
+    Path, Stroke, Style, TextStyle,
+};
+pub use executor::{exit, sleep_ticks, spawn, start, timeout, wait, JoinHandle};
+pub use gesture::{Gesture, Gestures};
+pub use input::{
+    gamepad, gamepads, input, key_down, key_pressed, key_released, mouse_down, mouse_pos,
+    mouse_pressed, mouse_released, mouse_wheel, touches,
+};
 pub use math::{F32Ext, V};
+pub use object::{Object, ObjectStyle, Outline, Shape};
+pub use paint::Paint;
+pub use time::{delta, tick, time};
+pub use transform::{pop_transform, push_transform, transform, with_transform};
 use vg_interface::*;
+pub use window::{reset_resolution, screen_size, set_resolution, viewport};
 
+/// Waits on several futures, taking the first ready branch in order. Unlike
+/// the randomized `futures::select!` this is deterministic across rollback
+pub use futures_util::select_biased as select;
+pub use futures_util::{future::FutureExt, join};
 pub use glam::{self, Mat3, Mat4, Vec2, Vec3, Vec4};
+pub use vg_interface::{
+    Align, Cap, Extend, Gamepad, Input, Join, Key, LogLevel, MouseButton, PadAxis, PadButton, ScaleMode,
+    Space, Touch, TouchPhase, Viewport,
+};
 
 /// Register a `Fn() -> impl Future<Output=()>` as the entrypoint for your game
 #[macro_export]
@@ -23,14 +63,6 @@ macro_rules! main {
     };
 }
 
-pub fn line(color: Vec4, points: impl IntoIterator<Item = Vec2>) {
-    ffi::dispatch(Request::Draw(Draw::Line {
-        color: color.into(),
-        points: points.into_iter().map(Into::into).collect(),
-    }))
-    .unwrap_empty();
-}
-
 /// Present the current frame to the screen, concluding this game tick
 pub async fn present() {
     wait(WaitReason::Present).await
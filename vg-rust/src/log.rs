@@ -0,0 +1,81 @@
+//! Logging into the engine, shown alongside its own logs
+//!
+//! Works like the `tracing` macros, with optional `key = value` fields before
+//! the message. Messages from re-simulated ticks are not repeated
+//!
+//! ```ignore
+//! vg::log::info!(score, lives = 3, "Level {} complete", level);
+//! ```
+
+use vg_interface::{LogLevel, Request};
+
+use crate::ffi;
+
+pub use crate::{
+    __vg_debug as debug, __vg_error as error, __vg_info as info, __vg_trace as trace,
+    __vg_warn as warn,
+};
+
+#[doc(hidden)]
+pub fn log(level: LogLevel, target: &str, message: String, fields: Vec<(String, String)>) {
+    ffi::dispatch(Request::Log {
+        level,
+        target: target.to_string(),
+        message,
+        fields,
+    })
+    .unwrap_empty();
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_log {
+    // Take fields off the front, one at a time
+    (@fields $level:expr, [$($fields:tt)*], $key:ident = $value:expr, $($rest:tt)+) => {
+        $crate::__vg_log!(@fields $level, [$($fields)* ($key, $value)], $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*], $key:ident, $($rest:tt)+) => {
+        $crate::__vg_log!(@fields $level, [$($fields)* ($key, $key)], $($rest)+)
+    };
+    (@fields $level:expr, [$(($key:ident, $value:expr))*], $($arg:tt)+) => {
+        $crate::log::log(
+            $level,
+            module_path!(),
+            format!($($arg)+),
+            vec![$((stringify!($key).to_string(), format!("{:?}", $value))),*],
+        )
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::__vg_log!(@fields $level, [], $($arg)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_trace {
+    ($($arg:tt)+) => { $crate::__vg_log!($crate::LogLevel::Trace, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_debug {
+    ($($arg:tt)+) => { $crate::__vg_log!($crate::LogLevel::Debug, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_info {
+    ($($arg:tt)+) => { $crate::__vg_log!($crate::LogLevel::Info, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_warn {
+    ($($arg:tt)+) => { $crate::__vg_log!($crate::LogLevel::Warn, $($arg)+) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vg_error {
+    ($($arg:tt)+) => { $crate::__vg_log!($crate::LogLevel::Error, $($arg)+) };
+}
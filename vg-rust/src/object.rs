@@ -0,0 +1,254 @@
+use std::cell::Cell;
+
+use vg_interface::{Request, SceneObject, Space};
+
+use crate::{ffi, transform, Mat3, Paint, Vec2, Vec4};
+
+thread_local! {
+    /// Lives in guest memory, so ids are handed out the same way on rollback
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Geometry of an object, before its transform
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Axis aligned rectangle between two corners
+    Rect {
+        min: Vec2,
+        max: Vec2,
+    },
+    Ellipse {
+        center: Vec2,
+        radii: Vec2,
+    },
+    /// Closed shape through all the points
+    Polygon(Vec<Vec2>),
+    /// Open polyline through all the points. Never filled
+    Line(Vec<Vec2>),
+}
+
+impl Shape {
+    pub fn circle(center: Vec2, radius: f32) -> Shape {
+        Shape::Ellipse {
+            center,
+            radii: Vec2::splat(radius),
+        }
+    }
+
+    pub fn polygon(points: impl IntoIterator<Item = Vec2>) -> Shape {
+        Shape::Polygon(points.into_iter().collect())
+    }
+
+    pub fn line(points: impl IntoIterator<Item = Vec2>) -> Shape {
+        Shape::Line(points.into_iter().collect())
+    }
+}
+
+impl From<Shape> for vg_interface::Shape {
+    fn from(shape: Shape) -> Self {
+        let points = |points: Vec<Vec2>| points.into_iter().map(Into::into).collect();
+        match shape {
+            Shape::Rect { min, max } => vg_interface::Shape::Rect {
+                min: min.into(),
+                max: max.into(),
+            },
+            Shape::Ellipse { center, radii } => vg_interface::Shape::Ellipse {
+                center: center.into(),
+                radii: radii.into(),
+            },
+            Shape::Polygon(p) => vg_interface::Shape::Polygon { points: points(p) },
+            Shape::Line(p) => vg_interface::Shape::Line { points: points(p) },
+        }
+    }
+}
+
+/// Outline of an object, painted like a fill or with colors spread evenly
+/// along it. A plain color or paint converts into a one unit wide outline
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outline {
+    width: f32,
+    space: Space,
+    paint: OutlinePaint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum OutlinePaint {
+    Paint(Paint),
+    Perimeter(Vec<Vec4>),
+}
+
+impl Outline {
+    pub fn new(paint: impl Into<Paint>) -> Outline {
+        Outline::with_paint(OutlinePaint::Paint(paint.into()))
+    }
+
+    /// Blend between colors going around the outline
+    pub fn gradient(colors: impl IntoIterator<Item = Vec4>) -> Outline {
+        Outline::with_paint(OutlinePaint::Perimeter(colors.into_iter().collect()))
+    }
+
+    fn with_paint(paint: OutlinePaint) -> Outline {
+        Outline {
+            width: 1.0,
+            space: Space::World,
+            paint,
+        }
+    }
+
+    /// Width in world units, scaled by the object transform
+    pub fn width(mut self, width: f32) -> Outline {
+        self.width = width;
+        self.space = Space::World;
+        self
+    }
+
+    /// Width in screen pixels, regardless of the object transform
+    pub fn view_width(mut self, width: f32) -> Outline {
+        self.width = width;
+        self.space = Space::View;
+        self
+    }
+}
+
+impl From<Vec4> for Outline {
+    fn from(color: Vec4) -> Outline {
+        Outline::new(color)
+    }
+}
+
+impl From<Paint> for Outline {
+    fn from(paint: Paint) -> Outline {
+        Outline::new(paint)
+    }
+}
+
+impl From<Outline> for vg_interface::Outline {
+    fn from(outline: Outline) -> Self {
+        vg_interface::Outline {
+            width: outline.width,
+            space: outline.space,
+            paint: match outline.paint {
+                OutlinePaint::Paint(paint) => vg_interface::OutlinePaint::Paint(paint.into()),
+                OutlinePaint::Perimeter(colors) => vg_interface::OutlinePaint::Perimeter(
+                    colors.into_iter().map(Into::into).collect(),
+                ),
+            },
+        }
+    }
+}
+
+/// Fill and outline of an object. A plain color or paint converts into a fill
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjectStyle {
+    pub fill: Option<Paint>,
+    pub outline: Option<Outline>,
+}
+
+impl ObjectStyle {
+    /// Only fill the object
+    pub fn fill(paint: impl Into<Paint>) -> ObjectStyle {
+        ObjectStyle {
+            fill: Some(paint.into()),
+            outline: None,
+        }
+    }
+
+    /// Only outline the object
+    pub fn outline(outline: impl Into<Outline>) -> ObjectStyle {
+        ObjectStyle {
+            fill: None,
+            outline: Some(outline.into()),
+        }
+    }
+
+    pub fn with_fill(mut self, paint: impl Into<Paint>) -> ObjectStyle {
+        self.fill = Some(paint.into());
+        self
+    }
+
+    pub fn with_outline(mut self, outline: impl Into<Outline>) -> ObjectStyle {
+        self.outline = Some(outline.into());
+        self
+    }
+}
+
+impl From<Vec4> for ObjectStyle {
+    fn from(color: Vec4) -> ObjectStyle {
+        ObjectStyle::fill(color)
+    }
+}
+
+impl From<Paint> for ObjectStyle {
+    fn from(paint: Paint) -> ObjectStyle {
+        ObjectStyle::fill(paint)
+    }
+}
+
+/// Handle to a shape that the engine keeps drawing every frame, without it
+/// being drawn again. Removed when the handle is dropped
+#[derive(Debug)]
+pub struct Object {
+    id: u64,
+    /// Last state sent to the engine
+    object: SceneObject,
+}
+
+impl Object {
+    /// Add an object to the world, with no transform
+    pub fn spawn(shape: Shape, style: impl Into<ObjectStyle>) -> Object {
+        let style = style.into();
+        let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+
+        let object = Object {
+            id,
+            object: SceneObject {
+                space: Space::World,
+                transform: transform::to_interface(Mat3::IDENTITY),
+                shape: shape.into(),
+                fill: style.fill.map(Into::into),
+                outline: style.outline.map(Into::into),
+            },
+        };
+        object.send();
+        object
+    }
+
+    pub fn set_shape(&mut self, shape: Shape) {
+        self.object.shape = shape.into();
+        self.send();
+    }
+
+    pub fn set_style(&mut self, style: impl Into<ObjectStyle>) {
+        let style = style.into();
+        self.object.fill = style.fill.map(Into::into);
+        self.object.outline = style.outline.map(Into::into);
+        self.send();
+    }
+
+    /// Place the object in the world
+    pub fn set_transform(&mut self, transform: Mat3) {
+        self.object.transform = transform::to_interface(transform);
+        self.send();
+    }
+
+    /// Move between the world, seen through cameras, and view space fixed on
+    /// the canvas over the world
+    pub fn set_space(&mut self, space: Space) {
+        self.object.space = space;
+        self.send();
+    }
+
+    fn send(&self) {
+        ffi::dispatch(Request::ObjectSet {
+            id: self.id,
+            object: self.object.clone(),
+        })
+        .unwrap_empty();
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        ffi::dispatch(Request::ObjectRemove { id: self.id }).unwrap_empty();
+    }
+}
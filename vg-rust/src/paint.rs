@@ -0,0 +1,175 @@
+use vg_interface::{ColorStop, Extend};
+
+use crate::{transform, Mat3, Vec2, Vec4};
+
+/// Color source of fills and strokes. A plain color converts into a solid
+/// paint. Positions are in the same units as the shape, and move along with it
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    Solid(Vec4),
+    /// Colors at offsets from 0 to 1 along the line from `start` to `end`
+    Linear {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<(f32, Vec4)>,
+        extend: Extend,
+    },
+    /// Colors at offsets from 0 to 1 from `center` out to `radius`
+    Radial {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<(f32, Vec4)>,
+        extend: Extend,
+    },
+    /// Colors at offsets from 0 to 1 around `center`, from `start_angle` to
+    /// `end_angle` in radians
+    Sweep {
+        center: Vec2,
+        start_angle: f32,
+        end_angle: f32,
+        stops: Vec<(f32, Vec4)>,
+        extend: Extend,
+    },
+    /// Image asset, its pixels placed by `transform`. Nothing is drawn until
+    /// it has loaded
+    Image {
+        path: String,
+        transform: Mat3,
+        extend: Extend,
+    },
+}
+
+impl Paint {
+    /// Blend between colors spread evenly from `start` to `end`
+    pub fn linear(start: Vec2, end: Vec2, colors: impl IntoIterator<Item = Vec4>) -> Paint {
+        Paint::Linear {
+            start,
+            end,
+            stops: spread(colors),
+            extend: Extend::Pad,
+        }
+    }
+
+    /// Blend between colors spread evenly from `center` out to `radius`
+    pub fn radial(center: Vec2, radius: f32, colors: impl IntoIterator<Item = Vec4>) -> Paint {
+        Paint::Radial {
+            center,
+            radius,
+            stops: spread(colors),
+            extend: Extend::Pad,
+        }
+    }
+
+    /// Blend between colors spread evenly around `center`, starting from the
+    /// x axis
+    pub fn sweep(center: Vec2, colors: impl IntoIterator<Item = Vec4>) -> Paint {
+        Paint::Sweep {
+            center,
+            start_angle: 0.0,
+            end_angle: std::f32::consts::TAU,
+            stops: spread(colors),
+            extend: Extend::Pad,
+        }
+    }
+
+    /// Tile an image asset, placing its pixels with a transform
+    pub fn image(path: impl Into<String>, transform: Mat3) -> Paint {
+        Paint::Image {
+            path: path.into(),
+            transform,
+            extend: Extend::Repeat,
+        }
+    }
+
+    /// What happens past the ends of a gradient or image. Solid paints don't
+    /// have any
+    pub fn extend(mut self, mode: Extend) -> Paint {
+        match &mut self {
+            Paint::Solid(_) => (),
+            Paint::Linear { extend, .. }
+            | Paint::Radial { extend, .. }
+            | Paint::Sweep { extend, .. }
+            | Paint::Image { extend, .. } => *extend = mode,
+        }
+        self
+    }
+}
+
+/// Offsets for colors spread evenly from 0 to 1
+fn spread(colors: impl IntoIterator<Item = Vec4>) -> Vec<(f32, Vec4)> {
+    let colors: Vec<_> = colors.into_iter().collect();
+    let last = colors.len().saturating_sub(1).max(1) as f32;
+    colors
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| (i as f32 / last, color))
+        .collect()
+}
+
+impl From<Vec4> for Paint {
+    fn from(color: Vec4) -> Paint {
+        Paint::Solid(color)
+    }
+}
+
+impl From<Paint> for vg_interface::Paint {
+    fn from(paint: Paint) -> Self {
+        let stops = |stops: Vec<(f32, Vec4)>| {
+            stops
+                .into_iter()
+                .map(|(offset, color)| ColorStop {
+                    offset,
+                    color: color.into(),
+                })
+                .collect()
+        };
+
+        match paint {
+            Paint::Solid(color) => vg_interface::Paint::Solid(color.into()),
+            Paint::Linear {
+                start,
+                end,
+                stops: s,
+                extend,
+            } => vg_interface::Paint::Linear {
+                start: start.into(),
+                end: end.into(),
+                stops: stops(s),
+                extend,
+            },
+            Paint::Radial {
+                center,
+                radius,
+                stops: s,
+                extend,
+            } => vg_interface::Paint::Radial {
+                center: center.into(),
+                radius,
+                stops: stops(s),
+                extend,
+            },
+            Paint::Sweep {
+                center,
+                start_angle,
+                end_angle,
+                stops: s,
+                extend,
+            } => vg_interface::Paint::Sweep {
+                center: center.into(),
+                start_angle,
+                end_angle,
+                stops: stops(s),
+                extend,
+            },
+            Paint::Image {
+                path,
+                transform: t,
+                extend,
+            } => vg_interface::Paint::Image {
+                path,
+                transform: transform::to_interface(t),
+                extend,
+            },
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Random numbers that are safe to use in game logic
+//!
+//! The generator lives in guest memory and starts from the session seed, so
+//! it is rolled back along with the game and every client sharing the seed
+//! gets the same sequence. Randomness from `std` or WASI is neither
+
+use std::{cell::Cell, ops::Range};
+
+use vg_interface::Request;
+
+use crate::ffi;
+
+thread_local! {
+    /// Seeded from the engine on first use
+    static STATE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Restart the sequence from a seed of your own
+pub fn seed(seed: u64) {
+    STATE.with(|state| state.set(Some(seed)));
+}
+
+/// Uniformly random 64 bits
+pub fn u64() -> u64 {
+    STATE.with(|state| {
+        let current = state
+            .get()
+            .unwrap_or_else(|| ffi::dispatch(Request::Seed).unwrap_seed());
+
+        // SplitMix64
+        let next = current.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state.set(Some(next));
+
+        let mut z = next;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+/// Uniformly random 32 bits
+pub fn u32() -> u32 {
+    (u64() >> 32) as u32
+}
+
+/// Between 0 inclusive and 1 exclusive
+pub fn f32() -> f32 {
+    // Use the top 24 bits, as many as the mantissa holds
+    (u64() >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Between `range.start` inclusive and `range.end` exclusive
+pub fn range(range: Range<f32>) -> f32 {
+    range.start + f32() * (range.end - range.start)
+}
+
+/// Integer between `range.start` inclusive and `range.end` exclusive. Returns
+/// `range.start` if the range is empty
+pub fn int(range: Range<i64>) -> i64 {
+    if range.end <= range.start {
+        return range.start;
+    }
+    let span = range.end.wrapping_sub(range.start) as u64;
+
+    // Scale into the span with a widening multiply, cheaper than a modulo
+    let offset = ((u64() as u128 * span as u128) >> 64) as u64;
+    range.start.wrapping_add(offset as i64)
+}
+
+/// True with a probability of `p`
+pub fn chance(p: f32) -> bool {
+    f32() < p
+}
+
+/// Random element of a slice, None if it is empty
+pub fn pick<T>(items: &[T]) -> Option<&T> {
+    match items.len() {
+        0 => None,
+        len => items.get(int(0..len as i64) as usize),
+    }
+}
@@ -0,0 +1,45 @@
+//! Save data kept between sessions, like high scores and settings
+//!
+//! Values are written to disk once the tick that saved them can no longer be
+//! rolled back. Typed values use nanoserde, so derive `SerBin` and `DeBin` on
+//! them
+
+use vg_interface::{DeBin, Request, SerBin};
+
+use crate::ffi;
+
+/// Raw bytes saved under a key
+pub fn read(key: impl Into<String>) -> Option<Vec<u8>> {
+    ffi::dispatch(Request::StorageRead { key: key.into() }).unwrap_storage()
+}
+
+/// Save raw bytes under a key
+pub fn write(key: impl Into<String>, bytes: Vec<u8>) {
+    ffi::dispatch(Request::StorageWrite {
+        key: key.into(),
+        bytes,
+    })
+    .unwrap_empty();
+}
+
+/// Load the saved value of a type. None if nothing was saved, or it can't be
+/// read as `T` anymore
+pub fn load<T: DeBin>() -> Option<T> {
+    load_key(std::any::type_name::<T>())
+}
+
+/// Save a value, replacing the earlier one of the same type. Values are keyed
+/// by type name, so moving or renaming the type loses them
+pub fn save<T: SerBin>(value: &T) {
+    save_key(std::any::type_name::<T>(), value);
+}
+
+/// Load a value saved under a key
+pub fn load_key<T: DeBin>(key: impl Into<String>) -> Option<T> {
+    T::deserialize_bin(&read(key)?).ok()
+}
+
+/// Save a value under a key
+pub fn save_key<T: SerBin>(key: impl Into<String>, value: &T) {
+    write(key, value.serialize_bin());
+}
@@ -0,0 +1,19 @@
+use vg_interface::Request;
+
+use crate::ffi;
+
+/// Index of the current tick, starting from 0
+pub fn tick() -> u64 {
+    ffi::dispatch(Request::Time).unwrap_time().tick
+}
+
+/// Fixed length of a tick in seconds
+pub fn delta() -> f32 {
+    ffi::dispatch(Request::Time).unwrap_time().delta
+}
+
+/// Seconds of game time simulated before the current tick. Advances by
+/// `delta()` every tick regardless of how fast the game actually runs
+pub fn time() -> f32 {
+    ffi::dispatch(Request::Time).unwrap_time().elapsed as f32
+}
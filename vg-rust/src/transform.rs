@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+
+use vg_interface::Transform;
+
+use crate::Mat3;
+
+thread_local! {
+    /// Lives in guest memory, so it is rolled back along with the game
+    static STACK: RefCell<Vec<Mat3>> = const { RefCell::new(vec![]) };
+}
+
+/// The transform applied to draws right now
+pub fn transform() -> Mat3 {
+    STACK.with(|stack| stack.borrow().last().copied().unwrap_or(Mat3::IDENTITY))
+}
+
+/// Apply a transform on top of the current one for all following draws
+pub fn push_transform(matrix: Mat3) {
+    let combined = transform() * matrix;
+    STACK.with(|stack| stack.borrow_mut().push(combined));
+}
+
+/// Return to the transform before the latest `push_transform`
+pub fn pop_transform() {
+    STACK.with(|stack| stack.borrow_mut().pop());
+}
+
+/// Apply a transform for all draws within `f`
+pub fn with_transform<T>(matrix: Mat3, f: impl FnOnce() -> T) -> T {
+    push_transform(matrix);
+    let result = f();
+    pop_transform();
+    result
+}
+
+/// Convert a 2D affine glam matrix for the runtime
+pub(crate) fn to_interface(matrix: Mat3) -> Transform {
+    Transform {
+        x_axis: matrix.x_axis.truncate().into(),
+        y_axis: matrix.y_axis.truncate().into(),
+        translation: matrix.z_axis.truncate().into(),
+    }
+}
@@ -0,0 +1,34 @@
+use vg_interface::{Request, Resolution, ScaleMode, Viewport};
+
+use crate::{ffi, Vec2, Vec4};
+
+/// Window state as seen by this tick
+pub fn viewport() -> Viewport {
+    ffi::dispatch(Request::Viewport).unwrap_viewport()
+}
+
+/// Size of the visible canvas in the units used for drawing. Zero when
+/// headless, unless a resolution is set
+pub fn screen_size() -> Vec2 {
+    viewport().canvas_size.into()
+}
+
+/// Draw in virtual units of some size, fitted onto the window by `mode`.
+/// Cursor and touch positions are reported in the same units. `clear` fills
+/// the background and any bars around the canvas
+pub fn set_resolution(size: Vec2, mode: ScaleMode, clear: Vec4) {
+    let resolution = Resolution {
+        size: size.into(),
+        mode,
+        clear: clear.into(),
+    };
+    ffi::dispatch(Request::SetResolution {
+        resolution: Some(resolution),
+    })
+    .unwrap_empty();
+}
+
+/// Go back to drawing in window pixels
+pub fn reset_resolution() {
+    ffi::dispatch(Request::SetResolution { resolution: None }).unwrap_empty();
+}
@@ -1,15 +1,65 @@
-use glam::{Mat4, Vec4};
+use std::collections::BTreeMap;
+
+use glam::{Affine2, Mat4, Vec2, Vec4};
 
 pub type Color = Vec4;
 
+/// Objects kept between frames, by id. Drawn in id order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scene {
+    objects: BTreeMap<u64, Object>,
+}
+
+impl Scene {
+    /// Add an object, or replace the one with the same id
+    pub fn insert(&mut self, id: u64, object: Object) {
+        self.objects.insert(id, object);
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<Object> {
+        self.objects.remove(&id)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Object> {
+        self.objects.get(&id)
+    }
+
+    /// All objects in drawing order
+    pub fn objects(&self) -> impl Iterator<Item = &Object> {
+        self.objects.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
+
 /// A game object that can have all sorts of properties on it
+#[derive(Clone, Debug, PartialEq)]
 pub struct Object {
     pub transform: Space<Mat4>,
+    pub shape: Shape,
     pub stroke: Option<Stroke>,
     pub fill: Option<Fill>,
 }
 
+/// Geometry of an object, before its transform
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Rect { min: Vec2, max: Vec2 },
+    Ellipse { center: Vec2, radii: Vec2 },
+    /// Closed shape through all the points
+    Polygon(Vec<Vec2>),
+    /// Open polyline through all the points. Only ever stroked
+    Line(Vec<Vec2>),
+}
+
 /// Coordinate space variant
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Space<T = ()> {
     /// Data is in world space
     World(T),
@@ -17,13 +67,65 @@ pub enum Space<T = ()> {
     View(T),
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Stroke {
     /// Thickness of the stroke
     pub thickness: Space<f32>,
-    /// Colors of the stroke, interpolated evenly around the entire stroke perimeter
-    pub colors: Vec<Color>,
+    pub paint: StrokePaint,
+}
+
+/// Color source of a stroke
+#[derive(Clone, Debug, PartialEq)]
+pub enum StrokePaint {
+    Paint(Paint),
+    /// Colors interpolated evenly around the entire stroke perimeter
+    Perimeter(Vec<Color>),
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Fill {
-    pub color: Color,
+    pub paint: Paint,
+}
+
+/// Color source of a fill or stroke. Positions are in the units of the shape
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    Solid(Color),
+    /// Colors at offsets from 0 to 1 along the line from `start` to `end`
+    Linear {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<(f32, Color)>,
+        extend: Extend,
+    },
+    /// Colors at offsets from 0 to 1 from `center` out to `radius`
+    Radial {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<(f32, Color)>,
+        extend: Extend,
+    },
+    /// Colors at offsets from 0 to 1 around `center`, between two angles in
+    /// radians
+    Sweep {
+        center: Vec2,
+        start_angle: f32,
+        end_angle: f32,
+        stops: Vec<(f32, Color)>,
+        extend: Extend,
+    },
+    /// Image asset, its pixels placed by `transform`
+    Image {
+        path: String,
+        transform: Affine2,
+        extend: Extend,
+    },
+}
+
+/// What a gradient or image does past its ends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Extend {
+    Pad,
+    Repeat,
+    Reflect,
 }